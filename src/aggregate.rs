@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fmt;
 use data::{Data, Number};
 use expr::Expr;
@@ -6,6 +7,8 @@ use expr::Expr;
 pub enum AggregateFunction {
     Average,
     Count,
+    Max,
+    Min,
     Sum,
 }
 
@@ -14,6 +17,8 @@ impl AggregateFunction {
         match name.to_lowercase().as_ref() {
             "avg" => Some(AggregateFunction::Average),
             "count" => Some(AggregateFunction::Count),
+            "max" => Some(AggregateFunction::Max),
+            "min" => Some(AggregateFunction::Min),
             "sum" => Some(AggregateFunction::Sum),
             _ => None,
         }
@@ -21,9 +26,11 @@ impl AggregateFunction {
 
     pub fn aggregate(&self) -> Aggregate {
         match self {
-            &AggregateFunction::Average => Aggregate::Average(Number::Int(0), 0),
+            &AggregateFunction::Average => Aggregate::Average(Some(Number::Int(0)), 0),
             &AggregateFunction::Count => Aggregate::Count(0),
-            &AggregateFunction::Sum => Aggregate::Sum(Number::Int(0)),
+            &AggregateFunction::Max => Aggregate::Max(Data::Null),
+            &AggregateFunction::Min => Aggregate::Min(Data::Null),
+            &AggregateFunction::Sum => Aggregate::Sum(Some(Number::Int(0))),
         }
     }
 }
@@ -33,6 +40,8 @@ impl fmt::Display for AggregateFunction {
         match self {
             &AggregateFunction::Average => write!(f, "avg"),
             &AggregateFunction::Count => write!(f, "count"),
+            &AggregateFunction::Max => write!(f, "max"),
+            &AggregateFunction::Min => write!(f, "min"),
             &AggregateFunction::Sum => write!(f, "sum"),
         }
     }
@@ -40,21 +49,53 @@ impl fmt::Display for AggregateFunction {
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Aggregate {
-    Average(Number, i64),
+    /// The running total of an average or sum is `None` once it has overflowed.
+    Average(Option<Number>, i64),
     Count(i64),
-    Sum(Number),
+    Max(Data),
+    Min(Data),
+    Sum(Option<Number>),
+    /// Applies only the first occurrence of each value to the inner aggregate.
+    Distinct(HashSet<Data>, Box<Aggregate>),
 }
 
 impl Aggregate {
+    /// Counts a row without looking at any of its values, as `count(*)` does.
+    pub fn apply_row(&mut self) {
+        if let &mut Aggregate::Count(ref mut acc) = self {
+            *acc += 1;
+        }
+    }
+
     pub fn apply(&mut self, value: Data) {
         match (self, value) {
-            (&mut Aggregate::Average(ref mut acc, ref mut count), Data::Number(ref d)) => {
-                *acc += d.clone();
+            (&mut Aggregate::Distinct(ref mut seen, ref mut inner), value) => {
+                if !seen.contains(&value) {
+                    seen.insert(value.clone());
+                    inner.apply(value);
+                }
+            },
+            (&mut Aggregate::Average(ref mut acc, ref mut count), Data::Number(ref n)) => {
+                *acc = acc.as_ref().and_then(|acc| acc.checked_add(n));
                 *count += 1;
             },
             (&mut Aggregate::Count(_), Data::Null) => {},
             (&mut Aggregate::Count(ref mut acc), _) => *acc += 1,
-            (&mut Aggregate::Sum(ref mut acc), Data::Number(ref n)) => *acc += n.clone(),
+            (&mut Aggregate::Max(_), Data::Null) => {},
+            (&mut Aggregate::Max(ref mut acc), value) => {
+                if *acc == Data::Null || value > *acc {
+                    *acc = value;
+                }
+            },
+            (&mut Aggregate::Min(_), Data::Null) => {},
+            (&mut Aggregate::Min(ref mut acc), value) => {
+                if *acc == Data::Null || value < *acc {
+                    *acc = value;
+                }
+            },
+            (&mut Aggregate::Sum(ref mut acc), Data::Number(ref n)) => {
+                *acc = acc.as_ref().and_then(|acc| acc.checked_add(n));
+            },
             _ => {},
         }
     }
@@ -62,9 +103,13 @@ impl Aggregate {
     pub fn final_value(&self) -> Data {
         match self {
             &Aggregate::Average(_, 0) => Data::Number(Number::Float(0.0)),
-            &Aggregate::Average(ref acc, ref count) => Data::Number(Number::Float(acc.as_float() / (*count as f64))),
+            &Aggregate::Average(Some(ref acc), ref count) => Data::Number(Number::Float(acc.as_float() / (*count as f64))),
+            &Aggregate::Average(None, _) => Data::Null,
             &Aggregate::Count(ref acc) => Data::Number(Number::Int(acc.clone())),
-            &Aggregate::Sum(ref acc) => Data::Number(acc.clone()),
+            &Aggregate::Max(ref acc) => acc.clone(),
+            &Aggregate::Min(ref acc) => acc.clone(),
+            &Aggregate::Sum(ref acc) => acc.clone().map_or(Data::Null, Data::Number),
+            &Aggregate::Distinct(_, ref inner) => inner.final_value(),
         }
     }
 }
@@ -72,7 +117,34 @@ impl Aggregate {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AggregateCall {
     pub function: AggregateFunction,
-    pub argument: Box<Expr>,
+    /// The expression to aggregate, or `None` for `count(*)`.
+    pub argument: Option<Box<Expr>>,
+    pub distinct: bool,
+}
+
+impl AggregateCall {
+    pub fn aggregate(&self) -> Aggregate {
+        let aggregate = self.function.aggregate();
+        if self.distinct {
+            Aggregate::Distinct(HashSet::new(), Box::new(aggregate))
+        } else {
+            aggregate
+        }
+    }
+}
+
+impl fmt::Display for AggregateCall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.function)?;
+        if self.distinct {
+            write!(f, "distinct ")?;
+        }
+
+        match self.argument {
+            Some(ref argument) => write!(f, "{})", argument),
+            None => write!(f, "*)"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -100,6 +172,16 @@ mod tests {
         assert_eq!(expected, apply_agg("sum", input));
     }
 
+    #[test]
+    fn sum_overflow() {
+        let input = data_vec![Data::from(i64::MAX as isize), 1, 2];
+        assert_eq!(Data::Null, apply_agg("sum", input.clone()));
+        assert_eq!(Data::Null, apply_agg("avg", input));
+
+        let input = data_vec![Data::from(i64::MAX as isize), -1];
+        assert_eq!(Data::Number(Number::Int(i64::MAX - 1)), apply_agg("sum", input));
+    }
+
     #[test]
     fn count() {
         let input = data_vec![Data::Null, 1, true, false, 2.0, "foo"];
@@ -121,6 +203,59 @@ mod tests {
         assert_eq!(expected, apply_agg("avg", input));
     }
 
+    #[test]
+    fn max_numbers() {
+        let input = data_vec![Data::Null, 1, 2.5, -3, Data::Null];
+        let expected = Data::Number(Number::Float(2.5));
+        assert_eq!(expected, apply_agg("max", input));
+    }
+
+    #[test]
+    fn min_numbers() {
+        let input = data_vec![Data::Null, 1, 2.5, -3, Data::Null];
+        let expected = Data::Number(Number::Int(-3));
+        assert_eq!(expected, apply_agg("min", input));
+    }
+
+    #[test]
+    fn max_min_strings() {
+        let input = data_vec!["banana", "apple", "cherry"];
+        assert_eq!(Data::from("cherry"), apply_agg("max", input.clone()));
+        assert_eq!(Data::from("apple"), apply_agg("min", input));
+    }
+
+    #[test]
+    fn max_min_nothing() {
+        assert_eq!(Data::Null, apply_agg("max", data_vec![]));
+        assert_eq!(Data::Null, apply_agg("min", data_vec![Data::Null]));
+    }
+
+    #[test]
+    fn count_rows() {
+        let mut agg = AggregateFunction::Count.aggregate();
+        agg.apply_row();
+        agg.apply_row();
+        assert_eq!(Data::Number(Number::Int(2)), agg.final_value());
+    }
+
+    #[test]
+    fn distinct() {
+        let input = data_vec![1, 2, 2, 3.0, 3, Data::Null, Data::Null];
+        assert_eq!(Data::Number(Number::Int(3)), apply_distinct_agg("count", input.clone()));
+        assert_eq!(Data::Number(Number::Float(6.0)), apply_distinct_agg("sum", input));
+    }
+
+    fn apply_distinct_agg(name: &str, input: Vec<Data>) -> Data {
+        let call = AggregateCall {
+            function: AggregateFunction::from_name(name).unwrap(),
+            argument: None,
+            distinct: true,
+        };
+        let mut agg = call.aggregate();
+        input.iter().for_each(|value| agg.apply(value.clone()));
+        agg.final_value()
+    }
+
     fn apply_agg(name: &str, input: Vec<Data>) -> Data {
         let mut agg = AggregateFunction::from_name(name).unwrap().aggregate();
         input.iter().for_each(|value| agg.apply(value.clone()));
@@ -1,6 +1,8 @@
+use std::cmp::Ordering;
 use std::fmt;
 use data::Data;
-use query::SortDirection;
+use query::{NullOrder, SortDirection};
+use row::Row;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Answer {
@@ -8,14 +10,88 @@ pub struct Answer {
     pub rows: Vec<Vec<Data>>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnswerError {
+    UnknownColumn(String),
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &AnswerError::UnknownColumn(ref name) => write!(f, "answer has no column {}", name),
+        }
+    }
+}
+
+/// A column to sort by, given as an index into each row.
+pub type SortKey = (usize, SortDirection, NullOrder);
+
 impl Answer {
-    pub fn sort(&mut self, column_indices: &[(usize, SortDirection)]) {
-        for &(index, ref direction) in column_indices.iter().rev() {
-            match direction {
-                &SortDirection::Asc => self.rows.sort_by(|a, b| a[index].cmp(&b[index])),
-                &SortDirection::Desc => self.rows.sort_by(|a, b| b[index].cmp(&a[index])),
+    /// Sorts the rows by every key at once. Later keys only break ties left by earlier ones.
+    pub fn sort(&mut self, keys: &[SortKey]) {
+        if keys.is_empty() {
+            return;
+        }
+
+        self.rows.sort_by(|a, b| compare_rows(a, b, keys));
+    }
+
+    /// Turns each row into a `Row` with a column for each of the answer's columns, so that the
+    /// answer can be queried like a table.
+    pub fn into_rows(self) -> Vec<Row> {
+        let columns = self.columns;
+        self.rows.into_iter()
+            .map(|values| {
+                let mut row = Row::new();
+                for (name, value) in columns.iter().zip(values) {
+                    row.insert_column(name.clone(), value);
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// Sorts the rows like `sort`, looking up each column by name. Returns the first name that
+    /// isn't a column without sorting anything.
+    pub fn sort_by_columns(&mut self, columns: &[(&str, SortDirection, NullOrder)]) -> Result<(), AnswerError> {
+        let mut keys = Vec::with_capacity(columns.len());
+        for &(name, ref direction, ref nulls) in columns.iter() {
+            match self.columns.iter().position(|column| column == name) {
+                Some(index) => keys.push((index, direction.clone(), nulls.clone())),
+                None => return Err(AnswerError::UnknownColumn(name.to_owned())),
             }
         }
+
+        self.sort(&keys);
+        Ok(())
+    }
+}
+
+/// Compares two rows key by key, returning the first difference.
+pub fn compare_rows(a: &[Data], b: &[Data], keys: &[SortKey]) -> Ordering {
+    for &(index, ref direction, ref nulls) in keys.iter() {
+        let ordering = compare(&a[index], &b[index], direction, nulls);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Compares two values, placing nulls according to `nulls` regardless of `direction`.
+fn compare(a: &Data, b: &Data, direction: &SortDirection, nulls: &NullOrder) -> Ordering {
+    let null_ordering = match nulls {
+        &NullOrder::First => Ordering::Less,
+        &NullOrder::Last => Ordering::Greater,
+    };
+
+    match (a, b, direction) {
+        (&Data::Null, &Data::Null, _) => Ordering::Equal,
+        (&Data::Null, _, _) => null_ordering,
+        (_, &Data::Null, _) => null_ordering.reverse(),
+        (a, b, &SortDirection::Asc) => a.cmp(b),
+        (a, b, &SortDirection::Desc) => b.cmp(a),
     }
 }
 
@@ -34,3 +110,63 @@ impl fmt::Display for Answer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use row::make_rows;
+
+    #[test]
+    fn into_rows() {
+        let answer = Answer {
+            columns: vec![String::from("a"), String::from("b")],
+            rows: vec![data_vec![1, "x"], data_vec![Data::Null, "y"]],
+        };
+
+        let expected: Vec<Row> = make_rows(vec!["a", "b"], vec![data_vec![1, "x"], data_vec![Data::Null, "y"]])
+            .into_iter()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(expected, answer.into_rows());
+    }
+
+    #[test]
+    fn sort_multiple_keys() {
+        let mut answer = Answer {
+            columns: vec![String::from("a"), String::from("b")],
+            rows: vec![
+                data_vec![2, 1],
+                data_vec![1, Data::Null],
+                data_vec![2, 3],
+                data_vec![1, 2],
+            ],
+        };
+
+        answer.sort(&[(0, SortDirection::Desc, NullOrder::Last), (1, SortDirection::Asc, NullOrder::First)]);
+        let expected = vec![
+            data_vec![2, 1],
+            data_vec![2, 3],
+            data_vec![1, Data::Null],
+            data_vec![1, 2],
+        ];
+        assert_eq!(expected, answer.rows);
+    }
+
+    #[test]
+    fn sort_by_columns() {
+        let mut answer = Answer {
+            columns: vec![String::from("a"), String::from("b")],
+            rows: vec![
+                data_vec![1, "y"],
+                data_vec![2, "x"],
+            ],
+        };
+
+        answer.sort_by_columns(&[("b", SortDirection::Asc, NullOrder::Last)]).unwrap();
+        assert_eq!(vec![data_vec![2, "x"], data_vec![1, "y"]], answer.rows);
+
+        let actual = answer.sort_by_columns(&[("a", SortDirection::Asc, NullOrder::Last), ("c", SortDirection::Asc, NullOrder::Last)]);
+        assert_eq!(Err(AnswerError::UnknownColumn(String::from("c"))), actual);
+        assert_eq!(vec![data_vec![2, "x"], data_vec![1, "y"]], answer.rows);
+    }
+}
@@ -1,7 +1,9 @@
 extern crate sql;
 extern crate clap;
 
+use std::process;
 use clap::{App, Arg};
+use sql::source::{ColumnType, CsvOptions, SourceError, Table};
 
 fn main() {
     let matches = App::new("sql")
@@ -10,14 +12,47 @@ fn main() {
         .arg(Arg::with_name("QUERY")
             .help("The query to run")
             .required(true))
+        .arg(Arg::with_name("type")
+            .long("type")
+            .value_name("COLUMN=TYPE")
+            .help("Overrides the inferred type of a CSV column (int, float, bool or string)")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1))
         .get_matches();
 
+    let mut csv_options = CsvOptions::default();
+    for column_type in matches.values_of("type").into_iter().flatten() {
+        let mut parts = column_type.splitn(2, '=');
+        let column = parts.next().unwrap();
+        match parts.next().and_then(ColumnType::from_name) {
+            Some(t) => csv_options.column_types.insert(column.to_owned(), t),
+            None => {
+                eprintln!("invalid column type: {}", column_type);
+                process::exit(1);
+            },
+        };
+    }
+
     let query_str = matches.value_of("QUERY").unwrap();
     let query = sql::parse(query_str).unwrap();
-    let source = sql::open_file(&query.from).unwrap();
-    
-    match sql::execute(query, source) {
-        Ok(answer) => println!("{}", answer),
-        Err(e) => eprintln!("{:?}", e),
+    let mut headers: Vec<String> = Vec::new();
+    let result = sql::execute_tables(query, |name: &str| -> Result<Table, SourceError> {
+        let table = sql::source::open_table(name, &csv_options)?;
+        headers.extend(table.columns.iter().flat_map(|columns| columns.iter().cloned()));
+        Ok(table)
+    });
+
+    match result {
+        Ok(answer) => {
+            // Overrides apply to every CSV file, so a column is only unknown if none of them has it.
+            let unknown = csv_options.column_types.keys().find(|column| !headers.contains(column));
+            if let Some(column) = unknown {
+                eprintln!("unknown column in --type: {}", column);
+                process::exit(1);
+            }
+            println!("{}", answer)
+        },
+        Err(e) => eprintln!("{}", e),
     }
 }
@@ -1,7 +1,6 @@
 use std::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
-use std::mem::transmute;
 use std::ops::{Add, AddAssign};
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -53,7 +52,7 @@ impl From<String> for Data {
     }
 }
 
-#[derive(Clone, Debug, PartialOrd)]
+#[derive(Clone, Debug)]
 pub enum Number {
     Int(i64),
     Float(f64),
@@ -66,6 +65,67 @@ impl Number {
             &Number::Float(f) => f,
         }
     }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            &Number::Int(i) => i == 0,
+            &Number::Float(f) => f == 0.0,
+        }
+    }
+
+    /// Adds two numbers, promoting to a float if either side is one. Returns `None` if integer
+    /// addition overflows.
+    pub fn checked_add(&self, rhs: &Number) -> Option<Number> {
+        match (self, rhs) {
+            (&Number::Int(i1), &Number::Int(i2)) => i1.checked_add(i2).map(Number::Int),
+            (a, b) => Some(Number::Float(a.as_float() + b.as_float())),
+        }
+    }
+
+    pub fn checked_sub(&self, rhs: &Number) -> Option<Number> {
+        match (self, rhs) {
+            (&Number::Int(i1), &Number::Int(i2)) => i1.checked_sub(i2).map(Number::Int),
+            (a, b) => Some(Number::Float(a.as_float() - b.as_float())),
+        }
+    }
+
+    pub fn checked_mul(&self, rhs: &Number) -> Option<Number> {
+        match (self, rhs) {
+            (&Number::Int(i1), &Number::Int(i2)) => i1.checked_mul(i2).map(Number::Int),
+            (a, b) => Some(Number::Float(a.as_float() * b.as_float())),
+        }
+    }
+
+    /// Divides two numbers. Integer division truncates toward zero. Returns `None` when dividing
+    /// by zero, whether the operands are integers or floats.
+    pub fn checked_div(&self, rhs: &Number) -> Option<Number> {
+        if rhs.is_zero() {
+            return None;
+        }
+
+        match (self, rhs) {
+            (&Number::Int(i1), &Number::Int(i2)) => i1.checked_div(i2).map(Number::Int),
+            (a, b) => Some(Number::Float(a.as_float() / b.as_float())),
+        }
+    }
+
+    pub fn checked_rem(&self, rhs: &Number) -> Option<Number> {
+        if rhs.is_zero() {
+            return None;
+        }
+
+        match (self, rhs) {
+            (&Number::Int(i1), &Number::Int(i2)) => i1.checked_rem(i2).map(Number::Int),
+            (a, b) => Some(Number::Float(a.as_float() % b.as_float())),
+        }
+    }
+
+    pub fn checked_neg(&self) -> Option<Number> {
+        match self {
+            &Number::Int(i) => i.checked_neg().map(Number::Int),
+            &Number::Float(f) => Some(Number::Float(-f)),
+        }
+    }
 }
 
 impl fmt::Display for Number {
@@ -87,6 +147,13 @@ impl PartialEq for Number {
 }
 
 impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Number {
     fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
@@ -98,11 +165,17 @@ impl Ord for Number {
 
 impl Hash for Number {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let val = match self {
-            &Number::Int(i) => i,
-            &Number::Float(f) => unsafe{ transmute(f) },
+        // Ints and floats are equal when their float values are, so both hash by that value. Zero
+        // and NaN each have more than one representation that compares equal.
+        let f = self.as_float();
+        let bits = if f == 0.0 {
+            0
+        } else if f.is_nan() {
+            f64::NAN.to_bits()
+        } else {
+            f.to_bits()
         };
-        val.hash(state);
+        bits.hash(state);
     }
 }
 
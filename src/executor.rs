@@ -1,16 +1,58 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
 use aggregate::{Aggregate, AggregateCall};
-use answer::Answer;
-use data::Data;
-use expr::Expr;
-use query::{Query, SortDirection};
+use answer::{Answer, SortKey};
+use data::{Data, Number};
+use expr::{Column, Expr};
+use join::{join, qualify};
+use query::{Query, Relation, SelectItem};
 use row::Row;
-use source::{Source, SourceError};
+use source::{Source, SourceError, Table};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExecuteError {
     SourceError(String),
     InvalidOrderClause(Expr),
+    NestedAggregate(AggregateCall),
+    MisplacedAggregate(AggregateCall),
+    UngroupedColumn(String),
+    UnknownTable(String),
+    AmbiguousColumn(String),
+    CorrelatedSubquery(String),
+    SubqueryColumns(Query),
+    SubqueryRows(Query),
+}
+
+impl fmt::Display for ExecuteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &ExecuteError::SourceError(ref description) => write!(f, "{}", description),
+            &ExecuteError::InvalidOrderClause(ref expr) => write!(f, "can't order by {}", expr),
+            &ExecuteError::NestedAggregate(ref call) => write!(f, "aggregate calls can't be nested: {}", call),
+            &ExecuteError::MisplacedAggregate(ref call) => {
+                write!(f, "aggregate calls can't be used in where, group by or join conditions: {}", call)
+            },
+            &ExecuteError::UngroupedColumn(ref name) => {
+                write!(f, "column {} must be grouped or used in an aggregate call", name)
+            },
+            &ExecuteError::UnknownTable(ref name) => write!(f, "unknown table {}", name),
+            &ExecuteError::AmbiguousColumn(ref name) => {
+                write!(f, "column {} is ambiguous, qualify it with a table name", name)
+            },
+            &ExecuteError::CorrelatedSubquery(ref name) => {
+                write!(f, "subqueries can't refer to column {} of an outer query", name)
+            },
+            &ExecuteError::SubqueryColumns(ref query) => {
+                write!(f, "subquery must select exactly one column: ({})", query)
+            },
+            &ExecuteError::SubqueryRows(ref query) => {
+                write!(f, "subquery used as a value returned more than one row: ({})", query)
+            },
+        }
+    }
 }
 
 impl From<SourceError> for ExecuteError {
@@ -19,45 +61,479 @@ impl From<SourceError> for ExecuteError {
     }
 }
 
+/// A column of the answer, after wildcards have been expanded.
+struct Field {
+    expr: Expr,
+    alias: Option<String>,
+}
+
+impl Field {
+    fn name(&self) -> String {
+        match self.alias {
+            Some(ref alias) => alias.clone(),
+            None => format!("{}", self.expr),
+        }
+    }
+}
+
+/// A table in the from clause, by the name that qualifies its columns.
+struct TableColumns {
+    name: String,
+    /// The table's columns, from its source or from the rows read from it. Only joined tables
+    /// are read before the query is resolved, so this is empty for a single table.
+    columns: HashSet<String>,
+}
+
 struct Executor {
     query: Query,
     aggregate_calls: Vec<AggregateCall>,
-    order_indices: Vec<(usize, SortDirection)>,
 }
 
 impl Executor {
-    fn new(query: Query) -> Result<Self, ExecuteError> {
-        let aggregates = query.select.iter()
-            .filter_map(|expr| expr.get_aggregate_call())
+    fn new(query: Query, tables: &[TableColumns]) -> Result<Self, ExecuteError> {
+        let query = Self::resolve_columns(query, tables)?;
+
+        // These clauses pick the rows that aggregates are computed from, so they can't use them.
+        let mut row_exprs: Vec<&Expr> = query.condition.iter().chain(query.group.iter()).collect();
+        row_exprs.extend(Self::join_conditions(&query.from));
+        if let Some(call) = row_exprs.into_iter().flat_map(|expr| expr.get_aggregate_calls()).next() {
+            return Err(ExecuteError::MisplacedAggregate(call));
+        }
+
+        // Aggregates may appear anywhere in the select, having and order clauses, including ones
+        // that aren't selected.
+        let mut exprs: Vec<&Expr> = query.select.iter()
+            .filter_map(|item| match item {
+                &SelectItem::Expr{ref expr, ..} => Some(expr),
+                &SelectItem::Wildcard => None,
+            })
             .collect();
+        exprs.extend(query.having.iter());
+        exprs.extend(query.order.iter().map(|field| &field.expr));
 
-        let mut order_indices = Vec::new();
-        for sort_field in query.order.iter() {
-            let position = query.select.iter().position(|select| select == &sort_field.expr);
-            let index = match position {
-                Some(i) => i,
-                None => return Err(ExecuteError::InvalidOrderClause(sort_field.expr.clone())),
-            };
-            let direction = sort_field.direction.clone().unwrap_or(SortDirection::Asc);
-            order_indices.push((index, direction));
+        let mut aggregates = Vec::new();
+        for expr in exprs {
+            for call in expr.get_aggregate_calls() {
+                let is_nested = call.argument.as_ref()
+                    .map_or(false, |argument| !argument.get_aggregate_calls().is_empty());
+                if is_nested {
+                    return Err(ExecuteError::NestedAggregate(call));
+                }
+
+                if !aggregates.contains(&call) {
+                    aggregates.push(call);
+                }
+            }
         }
 
-        Ok(Executor {
+        let executor = Executor {
             query: query,
             aggregate_calls: aggregates,
-            order_indices: order_indices,
+        };
+        executor.validate_grouping()?;
+        Ok(executor)
+    }
+
+    /// Finds the table of every column reference. Rows of a single table aren't qualified, so
+    /// neither are the columns that refer to them. In a join, every column is qualified, and an
+    /// unqualified name must belong to only one table. Names that no table has are left alone,
+    /// since they may be select aliases or missing from every row.
+    fn resolve_columns(query: Query, tables: &[TableColumns]) -> Result<Query, ExecuteError> {
+        let is_join = tables.len() > 1;
+        let mut resolve = |column: &Column| {
+            let mut matches: Vec<&TableColumns> = tables.iter()
+                .filter(|table| match column.table {
+                    Some(ref name) => table.name == *name,
+                    None => table.columns.contains(&column.name),
+                })
+                .collect();
+
+            match (matches.pop(), matches.pop()) {
+                (Some(_), Some(_)) => Err(ExecuteError::AmbiguousColumn(column.to_string())),
+                (Some(table), None) if is_join => {
+                    Ok(Column::qualified(table.name.clone(), column.name.clone()))
+                },
+                (Some(_), None) => Ok(Column::new(column.name.clone())),
+                (None, _) => match column.table {
+                    Some(ref name) => Err(ExecuteError::UnknownTable(name.clone())),
+                    None => Ok(column.clone()),
+                },
+            }
+        };
+
+        Self::map_exprs(query, &mut |expr| expr.map_columns(&mut resolve))
+    }
+
+    /// Runs each subquery in an expression once, replacing it with its answer. Subqueries can't
+    /// refer to the tables of the query they're in, or to `outer_columns`.
+    fn run_subqueries(query: Query, open: &mut Open, outer_columns: &[String]) -> Result<Query, ExecuteError> {
+        let outer_tables: Vec<String> = query.from.table_names().into_iter().map(String::from).collect();
+        let mut answers: HashMap<Query, Answer> = HashMap::new();
+
+        let mut replace = |expr: Expr| {
+            let subquery = match expr {
+                Expr::Subquery(ref query) |
+                Expr::InSubquery{ref query, ..} |
+                Expr::Exists(ref query) => (**query).clone(),
+                expr => return Ok(expr),
+            };
+
+            if !answers.contains_key(&subquery) {
+                let inner_tables = subquery.from.table_names();
+                let outer_column = Self::query_exprs(&subquery).into_iter()
+                    .flat_map(|expr| expr.columns())
+                    .find(|column| match column.table {
+                        Some(ref table) => !inner_tables.contains(&table.as_str()) && outer_tables.contains(table),
+                        None => false,
+                    });
+                if let Some(column) = outer_column {
+                    return Err(ExecuteError::CorrelatedSubquery(column.to_string()));
+                }
+
+                let answer = run_nested(subquery.clone(), open, outer_columns)?;
+                answers.insert(subquery.clone(), answer);
+            }
+
+            // Exists only looks at whether there are any rows, so it can select any columns.
+            let answer = &answers[&subquery];
+            let is_exists = match expr {
+                Expr::Exists(_) => true,
+                _ => false,
+            };
+            if !is_exists && answer.columns.len() != 1 {
+                return Err(ExecuteError::SubqueryColumns(subquery));
+            }
+            let mut values = answer.rows.iter().map(|row| row[0].clone());
+
+            match expr {
+                Expr::Subquery(_) => match (values.next(), values.next()) {
+                    (_, Some(_)) => Err(ExecuteError::SubqueryRows(subquery)),
+                    (value, None) => Ok(Expr::from(value.unwrap_or(Data::Null))),
+                },
+                Expr::InSubquery{expr, negated, ..} => Ok(Expr::InList {
+                    expr: expr,
+                    list: values.map(Expr::from).collect(),
+                    negated: negated,
+                }),
+                _ => Ok(Expr::Bool(!answer.rows.is_empty())),
+            }
+        };
+
+        Self::map_exprs(query, &mut |expr| expr.clone().transform(&mut replace))
+    }
+
+    /// Finds an unqualified name that isn't one of `columns` or a select alias, but is one of
+    /// `outer_columns`.
+    fn find_outer_column(query: &Query, columns: &[String], outer_columns: &[String]) -> Option<String> {
+        let aliases: Vec<&String> = query.select.iter()
+            .filter_map(|item| match item {
+                &SelectItem::Expr{alias: Some(ref alias), ..} => Some(alias),
+                _ => None,
+            })
+            .collect();
+
+        Self::query_exprs(query).into_iter()
+            .flat_map(|expr| expr.columns())
+            .filter(|column| column.table.is_none())
+            .map(|column| &column.name)
+            .find(|name| !columns.contains(name) && !aliases.contains(name) && outer_columns.contains(name))
+            .cloned()
+    }
+
+    /// Replaces every expression in a query with the result of `func`, except for the order
+    /// fields that refer to select aliases.
+    fn map_exprs<F>(mut query: Query, func: &mut F) -> Result<Query, ExecuteError>
+        where F: FnMut(&Expr) -> Result<Expr, ExecuteError>
+    {
+        for item in query.select.iter_mut() {
+            if let &mut SelectItem::Expr{ref mut expr, ref mut alias} = item {
+                let mapped = func(expr)?;
+                // The answer keeps the name the query gave the column.
+                if alias.is_none() && mapped != *expr {
+                    *alias = Some(expr.to_string());
+                }
+                *expr = mapped;
+            }
+        }
+
+        query.condition = match query.condition {
+            Some(ref condition) => Some(func(condition)?),
+            None => None,
+        };
+        query.group = query.group.iter()
+            .map(|expr| func(expr))
+            .collect::<Result<_, _>>()?;
+        query.having = match query.having {
+            Some(ref having) => Some(func(having)?),
+            None => None,
+        };
+        let aliases: Vec<Expr> = query.select.iter()
+            .filter_map(|item| match item {
+                &SelectItem::Expr{alias: Some(ref alias), ..} => Some(Expr::Column(Column::new(alias.clone()))),
+                _ => None,
+            })
+            .collect();
+        for field in query.order.iter_mut().filter(|field| !aliases.contains(&field.expr)) {
+            field.expr = func(&field.expr)?;
+        }
+        Self::map_join_conditions(&mut query.from, func)?;
+
+        Ok(query)
+    }
+
+    fn map_join_conditions<F>(relation: &mut Relation, func: &mut F) -> Result<(), ExecuteError>
+        where F: FnMut(&Expr) -> Result<Expr, ExecuteError>
+    {
+        if let &mut Relation::Join{ref mut left, ref mut right, ref mut condition, ..} = relation {
+            Self::map_join_conditions(left, func)?;
+            Self::map_join_conditions(right, func)?;
+            *condition = func(condition)?;
+        }
+        Ok(())
+    }
+
+    /// Returns every expression in a query, including join conditions.
+    fn query_exprs(query: &Query) -> Vec<&Expr> {
+        let mut exprs: Vec<&Expr> = query.select.iter()
+            .filter_map(|item| match item {
+                &SelectItem::Expr{ref expr, ..} => Some(expr),
+                &SelectItem::Wildcard => None,
+            })
+            .collect();
+        exprs.extend(query.condition.iter());
+        exprs.extend(query.group.iter());
+        exprs.extend(query.having.iter());
+        exprs.extend(query.order.iter().map(|field| &field.expr));
+        exprs.extend(Self::join_conditions(&query.from));
+        exprs
+    }
+
+    fn join_conditions(relation: &Relation) -> Vec<&Expr> {
+        let mut conditions = Vec::new();
+        let mut relations = vec![relation];
+        while let Some(relation) = relations.pop() {
+            if let &Relation::Join{ref left, ref right, ref condition, ..} = relation {
+                conditions.push(condition);
+                relations.push(left);
+                relations.push(right);
+            }
+        }
+        conditions
+    }
+
+    fn is_grouped(&self) -> bool {
+        !self.aggregate_calls.is_empty() || !self.query.group.is_empty()
+    }
+
+    /// Once rows are grouped, expressions can only refer to columns through the group by clause
+    /// or inside aggregate calls. Wildcards are checked when they're expanded, since the columns
+    /// of some sources aren't known until they're read.
+    fn validate_grouping(&self) -> Result<(), ExecuteError> {
+        if !self.is_grouped() {
+            return Ok(());
+        }
+
+        let mut exprs: Vec<&Expr> = self.query.select.iter()
+            .filter_map(|item| match item {
+                &SelectItem::Expr{ref expr, ..} => Some(expr),
+                &SelectItem::Wildcard => None,
+            })
+            .collect();
+        exprs.extend(self.query.having.iter());
+        exprs.extend(self.query.order.iter()
+            .map(|field| &field.expr)
+            .filter(|expr| !self.is_select_alias(expr)));
+
+        for expr in exprs {
+            if let Some(name) = self.find_ungrouped_column(expr) {
+                return Err(ExecuteError::UngroupedColumn(name));
+            }
+        }
+        Ok(())
+    }
+
+    fn find_ungrouped_column(&self, expr: &Expr) -> Option<String> {
+        if self.query.group.contains(expr) {
+            return None;
+        }
+
+        match expr {
+            &Expr::Column(ref column) => Some(column.to_string()),
+            &Expr::AggregateCall(_) => None,
+            _ => expr.children().into_iter().filter_map(|child| self.find_ungrouped_column(child)).next(),
+        }
+    }
+
+    fn is_select_alias(&self, expr: &Expr) -> bool {
+        let name = match expr {
+            &Expr::Column(Column{table: None, ref name}) => name,
+            _ => return false,
+        };
+
+        self.query.select.iter().any(|item| match item {
+            &SelectItem::Expr{alias: Some(ref alias), ..} => alias == name,
+            _ => false,
         })
     }
 
-    fn execute(&self, source: Source) -> Result<Answer, ExecuteError> {
-        let mut source = self.apply_condition(source);
+    /// Reads every row of each table in a join, from left to right, qualifying each column by
+    /// its table. Each table's rows come with its columns: the ones its source declares, the
+    /// ones its rows have, or the columns of a subquery's answer.
+    fn read_tables(relation: &Relation,
+                   open: &mut Open,
+                   outer_columns: &[String],
+                   tables: &mut Vec<(Vec<String>, Vec<Row>)>)
+                   -> Result<(), ExecuteError> {
+        match relation {
+            &Relation::Table{ref name, ..} => {
+                let table_name = relation.table_names()[0];
+                let table = open(name)?;
+                let rows = table.rows.collect::<Result<Vec<Row>, SourceError>>()?;
+                let columns = table.columns.unwrap_or_else(|| {
+                    let mut columns = Vec::new();
+                    for row in rows.iter() {
+                        add_columns(&mut columns, row);
+                    }
+                    columns.into_iter().map(|column| column.name).collect()
+                });
+                tables.push((columns, rows.into_iter().map(|row| qualify(row, table_name)).collect()));
+            },
+            &Relation::Subquery{ref query, ref alias} => {
+                let answer = run_nested(*query.clone(), open, outer_columns)?;
+                let columns = answer.columns.clone();
+                tables.push((columns, answer.into_rows().into_iter().map(|row| qualify(row, alias)).collect()));
+            },
+            &Relation::Join{ref left, ref right, ..} => {
+                Self::read_tables(left, open, outer_columns, tables)?;
+                Self::read_tables(right, open, outer_columns, tables)?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Joins the rows of each table, in the order they were read by `read_tables`.
+    fn join_tables<I>(relation: &Relation, tables: &mut I) -> Vec<Row>
+        where I: Iterator<Item = Vec<Row>>
+    {
+        match relation {
+            &Relation::Table{..} | &Relation::Subquery{..} => tables.next().unwrap_or_default(),
+            &Relation::Join{ref left, ref kind, ref right, ref condition} => {
+                let left_rows = Self::join_tables(left, tables);
+                let right_rows = Self::join_tables(right, tables);
+                join(left_rows, kind, right_rows, condition, &left.table_names(), &right.table_names())
+            },
+        }
+    }
+
+    /// Runs the query over `source`. A wildcard expands to `columns`, which the caller may fill in
+    /// while the source is read if they aren't known ahead of time.
+    fn execute(&self, source: Source, columns: &RefCell<Vec<Column>>) -> Result<Answer, ExecuteError> {
+        let mut source = Self::filter(source, &self.query.condition);
         source = self.compute_aggregates(source)?;
-        
-        let mut answer = self.apply_select(source)?;
-        answer.sort(&self.order_indices);
+        source = Self::filter(source, &self.query.having);
+
+        // Without an order clause or distinct rows, the limit can be applied while streaming so
+        // that we stop reading the source as soon as we have enough rows.
+        let stream_limit = self.query.order.is_empty() && !self.query.distinct;
+        if stream_limit {
+            source = self.apply_limit(source);
+        }
+
+        let rows = source.collect::<Result<Vec<Row>, SourceError>>()?;
+        let mut fields = self.expand_select(&columns.borrow())?;
+        let selected = fields.len();
+        let order_indices = self.get_order_indices(&mut fields)?;
+
+        let mut answer = self.apply_select(&fields, rows);
+        if self.query.distinct {
+            Self::remove_duplicates(&mut answer);
+        }
+        answer.sort(&order_indices);
+        Self::remove_hidden_columns(&mut answer, selected);
+        if !stream_limit {
+            self.limit_answer(&mut answer);
+        }
         Ok(answer)
     }
 
+    /// Replaces each wildcard in the select list with `columns`, which must all be grouped if the
+    /// rows are.
+    fn expand_select(&self, columns: &[Column]) -> Result<Vec<Field>, ExecuteError> {
+        let mut fields = Vec::new();
+        for item in self.query.select.iter() {
+            match item {
+                &SelectItem::Wildcard => {
+                    for column in columns.iter() {
+                        let expr = Expr::Column(column.clone());
+                        if self.is_grouped() && !self.query.group.contains(&expr) {
+                            return Err(ExecuteError::UngroupedColumn(column.to_string()));
+                        }
+                        fields.push(Field {
+                            expr: expr,
+                            alias: None,
+                        });
+                    }
+                },
+                &SelectItem::Expr{ref expr, ref alias} => {
+                    fields.push(Field {
+                        expr: expr.clone(),
+                        alias: alias.clone(),
+                    });
+                },
+            }
+        }
+        Ok(fields)
+    }
+
+    /// Resolves each order field to a column of the answer. Order fields can be 1-based positions
+    /// in the select list, aliases or any expression. Expressions that aren't selected are added
+    /// to `fields` as hidden columns, which are removed once the answer is sorted.
+    fn get_order_indices(&self, fields: &mut Vec<Field>) -> Result<Vec<SortKey>, ExecuteError> {
+        let selected = fields.len();
+        let mut order_indices = Vec::new();
+
+        for sort_field in self.query.order.iter() {
+            let index = match sort_field.expr {
+                Expr::Number(Number::Int(position)) => {
+                    if position < 1 || position as usize > selected {
+                        return Err(ExecuteError::InvalidOrderClause(sort_field.expr.clone()));
+                    }
+                    position as usize - 1
+                },
+                ref expr => match Self::find_field(fields, expr) {
+                    Some(i) => i,
+                    // Hidden columns would stop duplicate rows from being removed.
+                    None if self.query.distinct => {
+                        return Err(ExecuteError::InvalidOrderClause(expr.clone()));
+                    },
+                    None => {
+                        fields.push(Field {
+                            expr: expr.clone(),
+                            alias: None,
+                        });
+                        fields.len() - 1
+                    },
+                },
+            };
+
+            order_indices.push((index, sort_field.sort_direction(), sort_field.null_order()));
+        }
+        Ok(order_indices)
+    }
+
+    /// Finds the field that an order expression refers to. A bare name refers to the field with
+    /// that alias before it refers to a column with that name.
+    fn find_field(fields: &[Field], expr: &Expr) -> Option<usize> {
+        if let &Expr::Column(Column{table: None, ref name}) = expr {
+            let position = fields.iter().position(|field| field.alias.as_ref() == Some(name));
+            if position.is_some() {
+                return position;
+            }
+        }
+
+        fields.iter().position(|field| &field.expr == expr)
+    }
+
     fn build_aggregates(&self,
                         source: Source)
                         -> Result<HashMap<Vec<Data>, Vec<Aggregate>>, ExecuteError> {
@@ -69,10 +545,18 @@ impl Executor {
                 .or_insert_with(|| self.make_aggregates());
 
             for (i, aggregate) in group_aggregates.iter_mut().enumerate() {
-                aggregate.apply(self.aggregate_calls[i].argument.eval(&row));
+                match self.aggregate_calls[i].argument {
+                    Some(ref argument) => aggregate.apply(argument.eval(&row)),
+                    None => aggregate.apply_row(),
+                }
             }
         }
 
+        // Without a group by clause, aggregates always produce one row, even if there's no input.
+        if self.query.group.is_empty() && groups.is_empty() {
+            groups.insert(vec![], self.make_aggregates());
+        }
+
         Ok(groups)
     }
 
@@ -85,7 +569,10 @@ impl Executor {
                 row.fields.insert(Expr::AggregateCall(call), aggregate.final_value());
             }
             for (index, val) in group.iter().enumerate() {
-                row.fields.insert(self.query.group[index].clone(), val.clone());
+                match self.query.group[index] {
+                    Expr::Column(ref column) => row.insert(column.clone(), val.clone()),
+                    ref expr => { row.groups.insert(expr.clone(), val.clone()); },
+                }
             }
             rows.push(Ok(row));
         }
@@ -93,10 +580,10 @@ impl Executor {
         Box::new(rows.into_iter())
     }
 
-    fn apply_condition(&self, source: Source) -> Source {
-        let condition = match self.query.condition {
-            Some(ref cond) => cond.clone(),
-            None => return source,
+    fn filter(source: Source, condition: &Option<Expr>) -> Source {
+        let condition = match condition {
+            &Some(ref cond) => cond.clone(),
+            &None => return source,
         };
 
         Box::new(source.filter(move |row| {
@@ -107,8 +594,48 @@ impl Executor {
         }))
     }
 
+    fn apply_limit(&self, source: Source) -> Source {
+        match self.query.limit {
+            Some(ref limit) => {
+                // Only rows count toward the offset, so that errors still reach the caller.
+                let offset = limit.offset;
+                let mut skipped = 0;
+                let source = source.filter(move |row| match row {
+                    &Ok(_) if skipped < offset => {
+                        skipped += 1;
+                        false
+                    },
+                    _ => true,
+                });
+                Box::new(source.take(limit.count))
+            },
+            None => source,
+        }
+    }
+
+    fn remove_hidden_columns(answer: &mut Answer, selected: usize) {
+        answer.columns.truncate(selected);
+        for row in answer.rows.iter_mut() {
+            row.truncate(selected);
+        }
+    }
+
+    /// Keeps the first occurrence of each row.
+    fn remove_duplicates(answer: &mut Answer) {
+        let mut seen = HashSet::new();
+        answer.rows.retain(|row| seen.insert(row.clone()));
+    }
+
+    fn limit_answer(&self, answer: &mut Answer) {
+        if let Some(ref limit) = self.query.limit {
+            let offset = limit.offset.min(answer.rows.len());
+            answer.rows.drain(..offset);
+            answer.rows.truncate(limit.count);
+        }
+    }
+
     fn compute_aggregates(&self, source: Source) -> Result<Source, ExecuteError> {
-        if self.aggregate_calls.is_empty() {
+        if !self.is_grouped() {
             return Ok(source);
         }
 
@@ -116,29 +643,15 @@ impl Executor {
         Ok(self.aggregates_to_source(aggregates))
     }
 
-    fn apply_select(&self, source: Source) -> Result<Answer, ExecuteError> {
-        let mut rows = Vec::new();
-
-        for row in source {
-            let row = row?;
-            let output_row = self.query
-                .select
-                .iter()
-                .map(|field| field.eval(&row))
-                .collect();
-            rows.push(output_row);
-        }
+    fn apply_select(&self, fields: &[Field], rows: Vec<Row>) -> Answer {
+        let rows = rows.iter()
+            .map(|row| fields.iter().map(|field| field.expr.eval(row)).collect())
+            .collect();
 
-        Ok(Answer {
-            columns: self.get_columns(),
+        Answer {
+            columns: fields.iter().map(|field| field.name()).collect(),
             rows: rows,
-        })
-    }
-
-    fn get_columns(&self) -> Vec<String> {
-        self.query.select.iter()
-            .map(|field| format!("{}", field))
-            .collect()
+        }
     }
 
     fn build_group(&self, row: &Row) -> Vec<Data> {
@@ -149,13 +662,173 @@ impl Executor {
 
     fn make_aggregates(&self) -> Vec<Aggregate> {
         self.aggregate_calls.iter()
-            .map(|call| call.function.aggregate())
+            .map(|call| call.aggregate())
             .collect()
     }
 }
 
+/// Runs a query that reads from a single table.
 pub fn execute(query: Query, source: Source) -> Result<Answer, ExecuteError> {
-    Executor::new(query)?.execute(source)
+    let mut source = Some(source);
+    execute_tables(query, move |_: &str| {
+        source.take().ok_or_else(|| SourceError { description: "only one source was given".to_owned() })
+    })
+}
+
+/// Runs a query, calling `open` to read each table in its from clause. `open` can return a
+/// `Source`, or a `Table` for sources that know their columns before reading any rows.
+pub fn execute_tables<F, T>(query: Query, mut open: F) -> Result<Answer, ExecuteError>
+    where F: FnMut(&str) -> Result<T, SourceError>,
+          T: Into<Table>
+{
+    run(query, &mut |name: &str| open(name).map(Into::into))
+}
+
+/// Runs each common table expression once, in order, then runs the query with their answers in
+/// place of any files with the same names. Each expression can use the ones before it.
+fn run_with(mut query: Query, open: &mut Open, outer_columns: &[String]) -> Result<Answer, ExecuteError> {
+    let mut answers: Vec<(String, Answer)> = Vec::new();
+    for cte in mem::replace(&mut query.with, vec![]) {
+        let answer = run_nested(cte.query, &mut |name: &str| open_cte(&answers, name, open), outer_columns)?;
+        answers.push((cte.name, answer));
+    }
+
+    run_nested(query, &mut |name: &str| open_cte(&answers, name, open), outer_columns)
+}
+
+fn open_cte(answers: &[(String, Answer)], name: &str, open: &mut Open) -> Result<Table, SourceError> {
+    // Later expressions shadow earlier ones with the same name.
+    match answers.iter().rev().find(|&&(ref cte, _)| cte == name) {
+        Some(&(_, ref answer)) => Ok(Table {
+            columns: Some(answer.columns.clone()),
+            rows: Box::new(answer.clone().into_rows().into_iter().map(Ok)),
+        }),
+        None => open(name),
+    }
+}
+
+/// Adds each column of `row` that isn't in `columns` yet, keeping the order they're first seen.
+fn add_columns(columns: &mut Vec<Column>, row: &Row) {
+    // Rows of the same source usually have the same columns.
+    if columns.starts_with(&row.columns) {
+        return;
+    }
+
+    for column in row.columns.iter() {
+        if !columns.contains(column) {
+            columns.push(column.clone());
+        }
+    }
+}
+
+/// Passes the rows of `source` through, adding their columns to `columns` as they're read.
+fn record_columns(source: Source, columns: Rc<RefCell<Vec<Column>>>) -> Source {
+    Box::new(source.inspect(move |row| if let &Ok(ref row) = row {
+        add_columns(&mut columns.borrow_mut(), row);
+    }))
+}
+
+/// Returns the columns of a table, reading its first row if its source doesn't know them. The
+/// rest of its rows are assumed to have the same columns. A table with no rows has no known
+/// columns.
+fn peek_columns(table: Table) -> (Option<Vec<String>>, Table) {
+    if table.columns.is_some() {
+        return (table.columns.clone(), table);
+    }
+
+    let mut rows = table.rows;
+    let first = rows.next();
+    let columns = match first {
+        Some(Ok(ref row)) => Some(row.columns.iter().map(|column| column.name.clone()).collect()),
+        _ => None,
+    };
+    (columns, Table::from(Box::new(first.into_iter().chain(rows)) as Source))
+}
+
+/// Opens a table by name. Nested queries open their tables with the same function.
+type Open<'a> = dyn FnMut(&str) -> Result<Table, SourceError> + 'a;
+
+fn run(query: Query, open: &mut Open) -> Result<Answer, ExecuteError> {
+    run_nested(query, open, &[])
+}
+
+/// Runs a query that's nested in queries whose tables have `outer_columns`, which it can't refer
+/// to. This is checked once the query's own tables are opened, before any subqueries are run.
+fn run_nested(query: Query, open: &mut Open, outer_columns: &[String]) -> Result<Answer, ExecuteError> {
+    if !query.with.is_empty() {
+        return run_with(query, open, outer_columns);
+    }
+
+    let names: Vec<String> = query.from.table_names().into_iter().map(String::from).collect();
+    let mut tables = Vec::new();
+    let (columns, table) = match query.from {
+        Relation::Table{ref name, ..} => {
+            let (columns, table) = peek_columns(open(name)?);
+            (columns, Some(table))
+        },
+        Relation::Subquery{ref query, ..} => {
+            let answer = run_nested(*query.clone(), open, outer_columns)?;
+            let table = Table {
+                columns: Some(answer.columns.clone()),
+                rows: Box::new(answer.into_rows().into_iter().map(Ok)),
+            };
+            (table.columns.clone(), Some(table))
+        },
+        Relation::Join{..} => {
+            Executor::read_tables(&query.from, open, outer_columns, &mut tables)?;
+            let columns = tables.iter().flat_map(|&(ref columns, _)| columns.iter().cloned()).collect();
+            (Some(columns), None)
+        },
+    };
+
+    // Names that the query's own tables might have can't be told apart from outer ones.
+    let mut scope = outer_columns.to_vec();
+    if let Some(columns) = columns {
+        if let Some(name) = Executor::find_outer_column(&query, &columns, outer_columns) {
+            return Err(ExecuteError::CorrelatedSubquery(name));
+        }
+        scope.extend(columns);
+    }
+
+    let query = Executor::run_subqueries(query, open, &scope)?;
+    match table {
+        Some(table) => run_table(query, &names[0], table),
+        None => run_join(query, &names, tables),
+    }
+}
+
+fn run_table(query: Query, name: &str, table: Table) -> Result<Answer, ExecuteError> {
+    let tables = vec![TableColumns{name: name.to_owned(), columns: HashSet::new()}];
+    let columns = Rc::new(RefCell::new(Vec::new()));
+    let rows = match table.columns {
+        Some(names) => {
+            columns.borrow_mut().extend(names.into_iter().map(Column::new));
+            table.rows
+        },
+        None => record_columns(table.rows, columns.clone()),
+    };
+
+    Executor::new(query, &tables)?.execute(rows, &columns)
+}
+
+fn run_join(query: Query, names: &[String], rows: Vec<(Vec<String>, Vec<Row>)>) -> Result<Answer, ExecuteError> {
+    let tables: Vec<TableColumns> = names.iter()
+        .zip(rows.iter())
+        .map(|(name, &(ref columns, _))| TableColumns {
+            name: name.clone(),
+            columns: columns.iter().cloned().collect(),
+        })
+        .collect();
+    let columns = names.iter()
+        .zip(rows.iter())
+        .flat_map(|(name, &(ref columns, _))| {
+            columns.iter().map(move |column| Column::qualified(name.clone(), column.clone()))
+        })
+        .collect();
+
+    let executor = Executor::new(query, &tables)?;
+    let joined = Executor::join_tables(&executor.query.from, &mut rows.into_iter().map(|(_, rows)| rows));
+    executor.execute(Box::new(joined.into_iter().map(Ok)), &RefCell::new(columns))
 }
 
 #[cfg(test)]
@@ -164,7 +837,7 @@ mod tests {
     use aggregate::{AggregateCall, AggregateFunction};
     use data::Data;
     use expr::Expr;
-    use query::OrderField;
+    use query::{Limit, OrderField, SelectItem};
     use row::make_rows;
 
     #[test]
@@ -182,15 +855,20 @@ mod tests {
 
         let call = AggregateCall{
             function: AggregateFunction::Sum,
-            argument: Box::new(Expr::Column(String::from("a"))),
+            argument: Some(Box::new(Expr::Column(Column::new(String::from("a"))))),
+            distinct: false,
         };
 
         let query = Query {
-            select: vec![Expr::AggregateCall(call)],
-            from: String::new(),
+            with: vec![],
+            distinct: false,
+            select: vec![SelectItem::Expr{expr: Expr::AggregateCall(call), alias: None}],
+            from: Relation::Table{name: String::new(), alias: None},
             condition: None,
             group: vec![],
+            having: None,
             order: vec![],
+            limit: None,
         };
 
         let actual = execute(query, Box::new(source.into_iter())).unwrap();
@@ -216,11 +894,15 @@ mod tests {
         );
 
         let query = Query {
-            select: vec![Expr::Column(String::from("a"))],
-            from: String::new(),
+            with: vec![],
+            distinct: false,
+            select: vec![SelectItem::Expr{expr: Expr::Column(Column::new(String::from("a"))), alias: None}],
+            from: Relation::Table{name: String::new(), alias: None},
             condition: None,
             group: vec![],
+            having: None,
             order: vec![],
+            limit: None,
         };
 
         let actual = execute(query, Box::new(source.clone().into_iter())).unwrap();
@@ -238,6 +920,116 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn limit_stops_reading_source() {
+        let source = make_rows(
+            vec!["a"],
+            vec![
+                data_vec![1],
+                data_vec![2],
+                data_vec![3],
+            ],
+        );
+
+        let query = Query {
+            with: vec![],
+            distinct: false,
+            select: vec![SelectItem::Expr{expr: Expr::Column(Column::new(String::from("a"))), alias: None}],
+            from: Relation::Table{name: String::new(), alias: None},
+            condition: None,
+            group: vec![],
+            having: None,
+            order: vec![],
+            limit: Some(Limit{count: 4, offset: 1}),
+        };
+
+        // The source never ends, so this only returns if the limit stops reading from it.
+        let actual = execute(query, Box::new(source.into_iter().cycle())).unwrap();
+        let expected = Answer {
+            columns: vec![String::from("a")],
+            rows: vec![
+                data_vec![2],
+                data_vec![3],
+                data_vec![1],
+                data_vec![2],
+            ],
+        };
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn offset_keeps_source_errors() {
+        let error = SourceError{description: String::from("bad row")};
+        let mut source = make_rows(vec!["a"], vec![data_vec![1], data_vec![2]]);
+        source.insert(0, Err(error.clone()));
+
+        let query = Query {
+            with: vec![],
+            distinct: false,
+            select: vec![SelectItem::Expr{expr: Expr::Column(Column::new(String::from("a"))), alias: None}],
+            from: Relation::Table{name: String::new(), alias: None},
+            condition: None,
+            group: vec![],
+            having: None,
+            order: vec![],
+            limit: Some(Limit{count: 1, offset: 1}),
+        };
+
+        let actual = execute(query, Box::new(source.into_iter()));
+        assert_eq!(Err(ExecuteError::from(error)), actual);
+    }
+
+    #[test]
+    fn nested_aggregate() {
+        let inner = Expr::AggregateCall(AggregateCall{
+            function: AggregateFunction::Count,
+            argument: Some(Box::new(Expr::Column(Column::new(String::from("a"))))),
+            distinct: false,
+        });
+        let outer = AggregateCall{
+            function: AggregateFunction::Sum,
+            argument: Some(Box::new(inner)),
+            distinct: false,
+        };
+
+        let query = Query {
+            with: vec![],
+            distinct: false,
+            select: vec![SelectItem::Expr{expr: Expr::AggregateCall(outer.clone()), alias: None}],
+            from: Relation::Table{name: String::new(), alias: None},
+            condition: None,
+            group: vec![],
+            having: None,
+            order: vec![],
+            limit: None,
+        };
+
+        let actual = execute(query, Box::new(make_rows(vec!["a"], vec![]).into_iter()));
+        assert_eq!(Err(ExecuteError::NestedAggregate(outer)), actual);
+    }
+
+    #[test]
+    fn ungrouped_column() {
+        let query = Query {
+            with: vec![],
+            distinct: false,
+            select: vec![
+                SelectItem::Expr{expr: Expr::Column(Column::new(String::from("a"))), alias: None},
+                SelectItem::Expr{expr: Expr::Column(Column::new(String::from("b"))), alias: None},
+            ],
+            from: Relation::Table{name: String::new(), alias: None},
+            condition: None,
+            group: vec![Expr::Column(Column::new(String::from("a")))],
+            having: None,
+            order: vec![],
+            limit: None,
+        };
+
+        let actual = execute(query, Box::new(make_rows(vec!["a", "b"], vec![]).into_iter()));
+        assert_eq!(Err(ExecuteError::UngroupedColumn(String::from("b"))), actual);
+    }
+
     #[test]
     fn invalid_order_clause() {
         let source = make_rows(
@@ -246,17 +1038,22 @@ mod tests {
         );
 
         let query = Query {
+            with: vec![],
+            distinct: false,
             select: vec![],
-            from: String::new(),
+            from: Relation::Table{name: String::new(), alias: None},
             condition: None,
             group: vec![],
+            having: None,
             order: vec![OrderField {
-                expr: Expr::Column(String::from("a")),
+                expr: Expr::Number(Number::Int(1)),
                 direction: None,
+                nulls: None,
             }],
+            limit: None,
         };
         let actual = execute(query, Box::new(source.clone().into_iter()));
-        let expected = Err(ExecuteError::InvalidOrderClause(Expr::Column(String::from("a"))));
+        let expected = Err(ExecuteError::InvalidOrderClause(Expr::Number(Number::Int(1))));
         assert_eq!(expected, actual);
     }
 }
@@ -1,85 +1,600 @@
 use std::fmt::{self, Display, Formatter};
 use aggregate::AggregateCall;
 use data::{Data, Number};
+use query::Query;
 use row::Row;
 use token::Token;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Expr {
-    Column(String),
+    Column(Column),
     AggregateCall(AggregateCall),
     Number(Number),
+    String(String),
+    Bool(bool),
+    Null,
     BinaryExpr {
         left: Box<Expr>,
         op: BinaryOp,
         right: Box<Expr>,
     },
+    UnaryExpr {
+        op: UnaryOp,
+        expr: Box<Expr>,
+    },
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    InSubquery {
+        expr: Box<Expr>,
+        query: Box<Query>,
+        negated: bool,
+    },
+    /// A query that returns one column and at most one row, used as a value.
+    Subquery(Box<Query>),
+    Exists(Box<Query>),
+    Between {
+        expr: Box<Expr>,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        negated: bool,
+    },
+    Like {
+        expr: Box<Expr>,
+        pattern: Box<Expr>,
+        negated: bool,
+        case_insensitive: bool,
+    },
+    /// `case [operand] when ... then ... [else ...] end`. With an operand, each `when` value is
+    /// compared to it. Without one, each `when` value is a condition.
+    Case {
+        operand: Option<Box<Expr>>,
+        branches: Vec<(Expr, Expr)>,
+        else_result: Option<Box<Expr>>,
+    },
+}
+
+impl From<Data> for Expr {
+    fn from(data: Data) -> Self {
+        match data {
+            Data::Null => Expr::Null,
+            Data::Bool(b) => Expr::Bool(b),
+            Data::Number(n) => Expr::Number(n),
+            Data::String(s) => Expr::String(s),
+        }
+    }
+}
+
+/// A reference to a column, optionally qualified by the name or alias of its table.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Column {
+    pub table: Option<String>,
+    pub name: String,
+}
+
+impl Column {
+    pub fn new(name: String) -> Self {
+        Column {
+            table: None,
+            name: name,
+        }
+    }
+
+    pub fn qualified(table: String, name: String) -> Self {
+        Column {
+            table: Some(table),
+            name: name,
+        }
+    }
 }
 
+impl Display for Column {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(ref table) = self.table {
+            write!(f, "{}{}", Token::Identifier(table.clone()), Token::Dot)?;
+        }
+        Token::Identifier(self.name.clone()).fmt(f)
+    }
+}
+
+/// How tightly `is [not] null` and `is [not] distinct from` bind.
+pub const IS_PRECEDENCE: u8 = 4;
+
+/// How tightly `in`, `between`, `like` and `ilike` bind.
+pub const PREDICATE_PRECEDENCE: u8 = 6;
+
 impl Expr {
     pub fn eval(&self, row: &Row) -> Data {
+        // Only grouped rows need to look up whole expressions, so other rows skip the hashing.
+        if !row.groups.is_empty() {
+            if let Some(value) = row.groups.get(self) {
+                return value.clone();
+            }
+        }
+
         match *self {
             Expr::Column(_) => row.fields.get(self).cloned().unwrap_or(Data::Null),
             Expr::AggregateCall(_) => row.fields.get(self).cloned().unwrap_or(Data::Null),
             Expr::Number(ref n) => Data::Number(n.clone()),
+            Expr::String(ref s) => Data::String(s.clone()),
+            Expr::Bool(b) => Data::Bool(b),
+            Expr::Null => Data::Null,
             Expr::BinaryExpr{ref left, ref op, ref right} => op.eval(left.eval(row), right.eval(row)),
+            Expr::UnaryExpr{ref op, ref expr} => op.eval(expr.eval(row)),
+            Expr::IsNull{ref expr, negated} => Data::Bool((expr.eval(row) == Data::Null) != negated),
+            Expr::InList{ref expr, ref list, negated} => {
+                let value = expr.eval(row);
+                let items = list.iter().map(|item| item.eval(row)).collect();
+                negate(eval_in(value, items), negated)
+            },
+            // The executor replaces subqueries with their answers before evaluating anything.
+            Expr::InSubquery{..} | Expr::Subquery(_) | Expr::Exists(_) => Data::Null,
+            Expr::Between{ref expr, ref low, ref high, negated} => {
+                let value = expr.eval(row);
+                let above_low = BinaryOp::Gte.eval(value.clone(), low.eval(row));
+                let below_high = BinaryOp::Lte.eval(value, high.eval(row));
+                negate(BinaryOp::And.eval(above_low, below_high), negated)
+            },
+            Expr::Like{ref expr, ref pattern, negated, case_insensitive} => {
+                let matched = match (expr.eval(row), pattern.eval(row)) {
+                    (Data::String(ref value), Data::String(ref pattern)) if case_insensitive => {
+                        Data::Bool(like(&value.to_lowercase(), &pattern.to_lowercase()))
+                    },
+                    (Data::String(ref value), Data::String(ref pattern)) => Data::Bool(like(value, pattern)),
+                    _ => Data::Null,
+                };
+                negate(matched, negated)
+            },
+            Expr::Case{ref operand, ref branches, ref else_result} => {
+                let operand = operand.as_ref().map(|operand| operand.eval(row));
+                for &(ref when, ref then) in branches.iter() {
+                    let matched = match operand {
+                        Some(ref operand) => BinaryOp::Eq.eval(operand.clone(), when.eval(row)),
+                        None => when.eval(row),
+                    };
+
+                    if matched == Data::Bool(true) {
+                        return then.eval(row);
+                    }
+                }
+
+                else_result.as_ref().map_or(Data::Null, |else_result| else_result.eval(row))
+            },
         }
     }
 
-    pub fn get_aggregate_call(&self) -> Option<AggregateCall> {
-        let mut aggregate_call = None;
+    /// Returns how tightly this expression binds when printed next to an operator. Expressions
+    /// that aren't operators never need parentheses.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            &Expr::BinaryExpr{ref op, ..} => op.precedence(),
+            &Expr::UnaryExpr{ref op, ..} => op.precedence(),
+            &Expr::IsNull{..} => IS_PRECEDENCE,
+            &Expr::InList{..} |
+            &Expr::InSubquery{..} |
+            &Expr::Between{..} |
+            &Expr::Like{..} => PREDICATE_PRECEDENCE,
+            _ => u8::MAX,
+        }
+    }
+
+    /// Returns every distinct aggregate call in this expression, including ones nested inside
+    /// other aggregate calls, in the order they appear.
+    pub fn get_aggregate_calls(&self) -> Vec<AggregateCall> {
+        let mut aggregate_calls = Vec::new();
         self.recurse(&mut |expr: &Expr| {
             if let &Expr::AggregateCall(ref call) = expr {
-                aggregate_call = Some(call.clone());
+                if !aggregate_calls.contains(call) {
+                    aggregate_calls.push(call.clone());
+                }
             }
         });
-        aggregate_call
+        aggregate_calls
     }
 
-    fn recurse<F: FnMut(&Expr)>(&self, func: &mut F) {
+    /// Returns the expressions directly inside this one.
+    pub fn children(&self) -> Vec<&Expr> {
         match self {
-            &Expr::Column(_) => func(self),
-            &Expr::AggregateCall(ref call) => {
-                func(self);
-                call.argument.recurse(func);
+            &Expr::AggregateCall(ref call) => call.argument.iter().map(|argument| &**argument).collect(),
+            &Expr::Column(_) |
+            &Expr::Subquery(_) |
+            &Expr::Exists(_) |
+            &Expr::Number(_) |
+            &Expr::String(_) |
+            &Expr::Bool(_) |
+            &Expr::Null => vec![],
+            &Expr::BinaryExpr{ref left, op: _, ref right} => vec![left, right],
+            &Expr::UnaryExpr{op: _, ref expr} => vec![expr],
+            &Expr::IsNull{ref expr, negated: _} => vec![expr],
+            &Expr::InList{ref expr, ref list, negated: _} => {
+                let mut children = vec![&**expr];
+                children.extend(list.iter());
+                children
             },
-            &Expr::Number(_) => func(self),
-            &Expr::BinaryExpr{ref left, op: _, ref right} => {
-                func(self);
-                left.recurse(func);
-                right.recurse(func);
+            &Expr::InSubquery{ref expr, ..} => vec![expr],
+            &Expr::Between{ref expr, ref low, ref high, negated: _} => vec![expr, low, high],
+            &Expr::Like{ref expr, ref pattern, ..} => vec![expr, pattern],
+            &Expr::Case{ref operand, ref branches, ref else_result} => {
+                let mut children: Vec<&Expr> = operand.iter().map(|operand| &**operand).collect();
+                for &(ref when, ref then) in branches.iter() {
+                    children.push(when);
+                    children.push(then);
+                }
+                children.extend(else_result.iter().map(|else_result| &**else_result));
+                children
             },
         }
     }
+
+    /// Returns every column this expression refers to, not counting columns inside subqueries.
+    pub fn columns(&self) -> Vec<&Column> {
+        match self {
+            &Expr::Column(ref column) => vec![column],
+            _ => self.children().into_iter().flat_map(|child| child.columns()).collect(),
+        }
+    }
+
+    /// Returns a copy of this expression with every column reference replaced by the result of
+    /// `func`. Columns inside subqueries belong to the subquery, so they're left alone.
+    pub fn map_columns<E, F>(&self, func: &mut F) -> Result<Expr, E>
+        where F: FnMut(&Column) -> Result<Column, E>
+    {
+        self.clone().transform(&mut |expr| match expr {
+            Expr::Column(column) => Ok(Expr::Column(func(&column)?)),
+            expr => Ok(expr),
+        })
+    }
+
+    /// Rebuilds this expression from the bottom up, replacing each expression with the result of
+    /// `func` once its children have been transformed.
+    pub fn transform<E, F>(self, func: &mut F) -> Result<Expr, E>
+        where F: FnMut(Expr) -> Result<Expr, E>
+    {
+        let expr = match self {
+            Expr::AggregateCall(call) => Expr::AggregateCall(AggregateCall {
+                function: call.function,
+                argument: transform_boxed(call.argument, func)?,
+                distinct: call.distinct,
+            }),
+            Expr::BinaryExpr{left, op, right} => Expr::BinaryExpr {
+                left: Box::new(left.transform(func)?),
+                op: op,
+                right: Box::new(right.transform(func)?),
+            },
+            Expr::UnaryExpr{op, expr} => Expr::UnaryExpr {
+                op: op,
+                expr: Box::new(expr.transform(func)?),
+            },
+            Expr::IsNull{expr, negated} => Expr::IsNull {
+                expr: Box::new(expr.transform(func)?),
+                negated: negated,
+            },
+            Expr::InList{expr, list, negated} => Expr::InList {
+                expr: Box::new(expr.transform(func)?),
+                list: list.into_iter().map(|item| item.transform(func)).collect::<Result<_, E>>()?,
+                negated: negated,
+            },
+            Expr::InSubquery{expr, query, negated} => Expr::InSubquery {
+                expr: Box::new(expr.transform(func)?),
+                query: query,
+                negated: negated,
+            },
+            Expr::Between{expr, low, high, negated} => Expr::Between {
+                expr: Box::new(expr.transform(func)?),
+                low: Box::new(low.transform(func)?),
+                high: Box::new(high.transform(func)?),
+                negated: negated,
+            },
+            Expr::Like{expr, pattern, negated, case_insensitive} => Expr::Like {
+                expr: Box::new(expr.transform(func)?),
+                pattern: Box::new(pattern.transform(func)?),
+                negated: negated,
+                case_insensitive: case_insensitive,
+            },
+            Expr::Case{operand, branches, else_result} => {
+                let mut transformed = Vec::with_capacity(branches.len());
+                for (when, then) in branches {
+                    transformed.push((when.transform(func)?, then.transform(func)?));
+                }
+
+                Expr::Case {
+                    operand: transform_boxed(operand, func)?,
+                    branches: transformed,
+                    else_result: transform_boxed(else_result, func)?,
+                }
+            },
+            expr => expr,
+        };
+
+        func(expr)
+    }
+
+    fn recurse<F: FnMut(&Expr)>(&self, func: &mut F) {
+        func(self);
+        for child in self.children() {
+            child.recurse(func);
+        }
+    }
 }
 
 impl Display for Expr {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
-            &Expr::Column(ref name) => Token::Identifier(name.clone()).fmt(f),
-            &Expr::AggregateCall(ref call) => write!(f, "{}({})", call.function, call.argument),
-            &Expr::Number(ref n) => write!(f, "{}", n),
-            &Expr::BinaryExpr{ref left, ref op, ref right} => write!(f, "{} {} {}", left, op, right),
+            &Expr::Column(ref column) => column.fmt(f),
+            &Expr::AggregateCall(ref call) => call.fmt(f),
+            &Expr::Number(ref n) => Token::Number(n.clone()).fmt(f),
+            &Expr::String(ref s) => Token::String(s.clone()).fmt(f),
+            &Expr::Bool(true) => Token::True.fmt(f),
+            &Expr::Bool(false) => Token::False.fmt(f),
+            &Expr::Null => Token::Null.fmt(f),
+            &Expr::BinaryExpr{ref left, ref op, ref right} => {
+                // Operators are left-associative, so the right operand also needs parentheses
+                // when it binds exactly as tightly as this operator.
+                format_operand(f, left, left.precedence() < op.precedence())?;
+                write!(f, " {} ", op)?;
+                format_operand(f, right, right.precedence() <= op.precedence())
+            },
+            &Expr::UnaryExpr{ref op, ref expr} => {
+                write!(f, "{}", op)?;
+                if *op == UnaryOp::Not {
+                    write!(f, " ")?;
+                }
+                format_operand(f, expr, expr.precedence() < op.precedence())
+            },
+            &Expr::IsNull{ref expr, negated} => {
+                format_operand(f, expr, expr.precedence() < IS_PRECEDENCE)?;
+                write!(f, " {}", Token::Is)?;
+                if negated {
+                    write!(f, " {}", Token::Not)?;
+                }
+                write!(f, " {}", Token::Null)
+            },
+            &Expr::InList{ref expr, ref list, negated} => {
+                format_operand(f, expr, expr.precedence() < PREDICATE_PRECEDENCE)?;
+                format_negation(f, negated)?;
+                let list: Vec<String> = list.iter().map(|item| format!("{}", item)).collect();
+                write!(f, " {} ({})", Token::In, list.join(", "))
+            },
+            &Expr::InSubquery{ref expr, ref query, negated} => {
+                format_operand(f, expr, expr.precedence() < PREDICATE_PRECEDENCE)?;
+                format_negation(f, negated)?;
+                write!(f, " {} ({})", Token::In, query)
+            },
+            &Expr::Subquery(ref query) => write!(f, "({})", query),
+            &Expr::Exists(ref query) => write!(f, "{} ({})", Token::Exists, query),
+            &Expr::Between{ref expr, ref low, ref high, negated} => {
+                format_operand(f, expr, expr.precedence() < PREDICATE_PRECEDENCE)?;
+                format_negation(f, negated)?;
+                write!(f, " {} ", Token::Between)?;
+                format_operand(f, low, low.precedence() <= PREDICATE_PRECEDENCE)?;
+                write!(f, " {} ", Token::And)?;
+                format_operand(f, high, high.precedence() <= PREDICATE_PRECEDENCE)
+            },
+            &Expr::Like{ref expr, ref pattern, negated, case_insensitive} => {
+                format_operand(f, expr, expr.precedence() < PREDICATE_PRECEDENCE)?;
+                format_negation(f, negated)?;
+                let op = if case_insensitive { Token::ILike } else { Token::Like };
+                write!(f, " {} ", op)?;
+                format_operand(f, pattern, pattern.precedence() <= PREDICATE_PRECEDENCE)
+            },
+            &Expr::Case{ref operand, ref branches, ref else_result} => {
+                write!(f, "{}", Token::Case)?;
+                if let Some(ref operand) = *operand {
+                    write!(f, " {}", operand)?;
+                }
+                for &(ref when, ref then) in branches.iter() {
+                    write!(f, " {} {} {} {}", Token::When, when, Token::Then, then)?;
+                }
+                if let Some(ref else_result) = *else_result {
+                    write!(f, " {} {}", Token::Else, else_result)?;
+                }
+                write!(f, " {}", Token::End)
+            },
         }
     }
 }
 
+fn transform_boxed<E, F>(expr: Option<Box<Expr>>, func: &mut F) -> Result<Option<Box<Expr>>, E>
+    where F: FnMut(Expr) -> Result<Expr, E>
+{
+    match expr {
+        Some(expr) => Ok(Some(Box::new(expr.transform(func)?))),
+        None => Ok(None),
+    }
+}
+
+fn format_negation(f: &mut Formatter, negated: bool) -> fmt::Result {
+    if negated {
+        write!(f, " {}", Token::Not)
+    } else {
+        Ok(())
+    }
+}
+
+fn negate(value: Data, negated: bool) -> Data {
+    if negated {
+        UnaryOp::Not.eval(value)
+    } else {
+        value
+    }
+}
+
+/// `value in (items)` is true if any item equals the value. Otherwise it's null if any of the
+/// comparisons were null, and false if none were.
+fn eval_in(value: Data, items: Vec<Data>) -> Data {
+    let mut result = Data::Bool(false);
+    for item in items {
+        match BinaryOp::Eq.eval(value.clone(), item) {
+            Data::Bool(true) => return Data::Bool(true),
+            Data::Null => result = Data::Null,
+            _ => {},
+        }
+    }
+    result
+}
+
+/// Matches a SQL `like` pattern, where `%` matches any sequence of characters, `_` matches any
+/// single character and a backslash matches the character after it literally.
+fn like(value: &str, pattern: &str) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    // The positions to resume from if a `%` needs to match more characters.
+    let mut backtrack = None;
+    let (mut v, mut p) = (0, 0);
+
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(&'%') => {
+                p += 1;
+                backtrack = Some((v, p));
+                continue;
+            },
+            Some(&'_') => {
+                v += 1;
+                p += 1;
+                continue;
+            },
+            Some(&'\\') if pattern.get(p + 1) == Some(&value[v]) => {
+                v += 1;
+                p += 2;
+                continue;
+            },
+            Some(&c) if c != '\\' && c == value[v] => {
+                v += 1;
+                p += 1;
+                continue;
+            },
+            _ => {},
+        }
+
+        match backtrack {
+            Some((backtrack_v, backtrack_p)) => {
+                v = backtrack_v + 1;
+                p = backtrack_p;
+                backtrack = Some((v, p));
+            },
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+fn format_operand(f: &mut Formatter, expr: &Expr, parenthesize: bool) -> fmt::Result {
+    if parenthesize {
+        write!(f, "({})", expr)
+    } else {
+        write!(f, "{}", expr)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum BinaryOp {
+    And,
+    Or,
     Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    IsDistinctFrom,
+    IsNotDistinctFrom,
 }
 
 impl BinaryOp {
     pub fn maybe_from(t: &Token) -> Option<Self> {
         match t {
+            &Token::And => Some(BinaryOp::And),
+            &Token::Or => Some(BinaryOp::Or),
             &Token::Eq => Some(BinaryOp::Eq),
+            &Token::Neq => Some(BinaryOp::Neq),
+            &Token::Lt => Some(BinaryOp::Lt),
+            &Token::Lte => Some(BinaryOp::Lte),
+            &Token::Gt => Some(BinaryOp::Gt),
+            &Token::Gte => Some(BinaryOp::Gte),
+            &Token::Plus => Some(BinaryOp::Add),
+            &Token::Minus => Some(BinaryOp::Sub),
+            &Token::Star => Some(BinaryOp::Mul),
+            &Token::Slash => Some(BinaryOp::Div),
+            &Token::Percent => Some(BinaryOp::Rem),
             _ => None,
         }
     }
 
+    /// Higher numbers bind more tightly.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            &BinaryOp::Or => 1,
+            &BinaryOp::And => 2,
+            &BinaryOp::IsDistinctFrom |
+            &BinaryOp::IsNotDistinctFrom => IS_PRECEDENCE,
+            &BinaryOp::Eq |
+            &BinaryOp::Neq |
+            &BinaryOp::Lt |
+            &BinaryOp::Lte |
+            &BinaryOp::Gt |
+            &BinaryOp::Gte => 5,
+            &BinaryOp::Add |
+            &BinaryOp::Sub => 7,
+            &BinaryOp::Mul |
+            &BinaryOp::Div |
+            &BinaryOp::Rem => 8,
+        }
+    }
+
+    /// Follows SQL's three-valued logic: null stands for an unknown value, so comparing it with
+    /// anything gives null, and `and`/`or` only give null when the unknown side could change the
+    /// result.
     pub fn eval(&self, left: Data, right: Data) -> Data {
         match self {
+            &BinaryOp::And => match (left, right) {
+                (Data::Bool(false), _) | (_, Data::Bool(false)) => Data::Bool(false),
+                (Data::Bool(true), Data::Bool(true)) => Data::Bool(true),
+                _ => Data::Null,
+            },
+            &BinaryOp::Or => match (left, right) {
+                (Data::Bool(true), _) | (_, Data::Bool(true)) => Data::Bool(true),
+                (Data::Bool(false), Data::Bool(false)) => Data::Bool(false),
+                _ => Data::Null,
+            },
+            &BinaryOp::IsDistinctFrom => Data::Bool(left != right),
+            &BinaryOp::IsNotDistinctFrom => Data::Bool(left == right),
+            _ if left == Data::Null || right == Data::Null => Data::Null,
             &BinaryOp::Eq => Data::Bool(left == right),
+            &BinaryOp::Neq => Data::Bool(left != right),
+            &BinaryOp::Lt => Data::Bool(left < right),
+            &BinaryOp::Lte => Data::Bool(left <= right),
+            &BinaryOp::Gt => Data::Bool(left > right),
+            &BinaryOp::Gte => Data::Bool(left >= right),
+            &BinaryOp::Add => Self::eval_arithmetic(left, right, Number::checked_add),
+            &BinaryOp::Sub => Self::eval_arithmetic(left, right, Number::checked_sub),
+            &BinaryOp::Mul => Self::eval_arithmetic(left, right, Number::checked_mul),
+            &BinaryOp::Div => Self::eval_arithmetic(left, right, Number::checked_div),
+            &BinaryOp::Rem => Self::eval_arithmetic(left, right, Number::checked_rem),
+        }
+    }
+
+    /// Arithmetic on anything other than two numbers, integer overflow and division by zero all
+    /// evaluate to null.
+    fn eval_arithmetic<F>(left: Data, right: Data, func: F) -> Data
+        where F: Fn(&Number, &Number) -> Option<Number>
+    {
+        match (left, right) {
+            (Data::Number(ref l), Data::Number(ref r)) => func(l, r).map(Data::Number).unwrap_or(Data::Null),
+            _ => Data::Null,
         }
     }
 }
@@ -87,7 +602,63 @@ impl BinaryOp {
 impl fmt::Display for BinaryOp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            &BinaryOp::And => write!(f, "and"),
+            &BinaryOp::Or => write!(f, "or"),
             &BinaryOp::Eq => write!(f, "="),
+            &BinaryOp::Neq => write!(f, "!="),
+            &BinaryOp::Lt => write!(f, "<"),
+            &BinaryOp::Lte => write!(f, "<="),
+            &BinaryOp::Gt => write!(f, ">"),
+            &BinaryOp::Gte => write!(f, ">="),
+            &BinaryOp::Add => write!(f, "+"),
+            &BinaryOp::Sub => write!(f, "-"),
+            &BinaryOp::Mul => write!(f, "*"),
+            &BinaryOp::Div => write!(f, "/"),
+            &BinaryOp::Rem => write!(f, "%"),
+            &BinaryOp::IsDistinctFrom => write!(f, "is distinct from"),
+            &BinaryOp::IsNotDistinctFrom => write!(f, "is not distinct from"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    Not,
+    Neg,
+}
+
+impl UnaryOp {
+    pub fn maybe_from(t: &Token) -> Option<Self> {
+        match t {
+            &Token::Not => Some(UnaryOp::Not),
+            &Token::Minus => Some(UnaryOp::Neg),
+            _ => None,
+        }
+    }
+
+    /// `not` binds more loosely than comparisons, so `not a = b` means `not (a = b)`.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            &UnaryOp::Not => 3,
+            &UnaryOp::Neg => 9,
+        }
+    }
+
+    pub fn eval(&self, value: Data) -> Data {
+        match (self, value) {
+            (&UnaryOp::Not, Data::Bool(b)) => Data::Bool(!b),
+            (&UnaryOp::Not, _) => Data::Null,
+            (&UnaryOp::Neg, Data::Number(n)) => n.checked_neg().map(Data::Number).unwrap_or(Data::Null),
+            (&UnaryOp::Neg, _) => Data::Null,
+        }
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &UnaryOp::Not => write!(f, "not"),
+            &UnaryOp::Neg => write!(f, "-"),
         }
     }
 }
@@ -101,11 +672,11 @@ mod tests {
     #[test]
     fn eval_column() {
         let mut row = Row::new();
-        row.fields.insert(Expr::Column(String::from("a")), Data::Number(Number::Int(0)));
-        row.fields.insert(Expr::Column(String::from("b")), Data::Number(Number::Int(1)));
-        row.fields.insert(Expr::Column(String::from("c")), Data::Number(Number::Int(2)));
+        row.fields.insert(Expr::Column(Column::new(String::from("a"))), Data::Number(Number::Int(0)));
+        row.fields.insert(Expr::Column(Column::new(String::from("b"))), Data::Number(Number::Int(1)));
+        row.fields.insert(Expr::Column(Column::new(String::from("c"))), Data::Number(Number::Int(2)));
 
-        let expr = Expr::Column(String::from("b"));
+        let expr = Expr::Column(Column::new(String::from("b")));
         assert_eq!(Data::Number(Number::Int(1)), expr.eval(&row));
     }
 
@@ -113,7 +684,8 @@ mod tests {
     fn eval_aggregate_function() {
         let agg_call = AggregateCall {
             function: AggregateFunction::Sum,
-            argument: Box::new(Expr::Column(String::from("a"))),
+            argument: Some(Box::new(Expr::Column(Column::new(String::from("a"))))),
+            distinct: false,
         };
 
         let mut row = Row::new();
@@ -123,6 +695,31 @@ mod tests {
         assert_eq!(Data::Number(Number::Int(4)), expr.eval(&row));
     }
 
+    #[test]
+    fn get_aggregate_calls() {
+        let sum = AggregateCall {
+            function: AggregateFunction::Sum,
+            argument: Some(Box::new(Expr::Column(Column::new(String::from("a"))))),
+            distinct: false,
+        };
+        let count = AggregateCall {
+            function: AggregateFunction::Count,
+            argument: Some(Box::new(Expr::Column(Column::new(String::from("a"))))),
+            distinct: false,
+        };
+
+        let expr = Expr::BinaryExpr {
+            left: Box::new(Expr::BinaryExpr {
+                left: Box::new(Expr::AggregateCall(sum.clone())),
+                op: BinaryOp::Div,
+                right: Box::new(Expr::AggregateCall(count.clone())),
+            }),
+            op: BinaryOp::Add,
+            right: Box::new(Expr::AggregateCall(sum.clone())),
+        };
+        assert_eq!(vec![sum, count], expr.get_aggregate_calls());
+    }
+
     #[test]
     fn eval_number() {
         let expr = Expr::Number(Number::Int(5));
@@ -130,17 +727,115 @@ mod tests {
         assert_eq!(Data::Number(Number::Int(5)), expr.eval(&row));
     }
 
+    #[test]
+    fn eval_literals() {
+        let row = Row::new();
+        assert_eq!(Data::from("foo"), Expr::String(String::from("foo")).eval(&row));
+        assert_eq!(Data::Bool(true), Expr::Bool(true).eval(&row));
+        assert_eq!(Data::Null, Expr::Null.eval(&row));
+    }
+
+    #[test]
+    fn eval_is_null() {
+        let row = Row::new();
+        let is_null = |expr: Expr, negated: bool| Expr::IsNull{expr: Box::new(expr), negated: negated}.eval(&row);
+        assert_eq!(Data::Bool(true), is_null(Expr::Null, false));
+        assert_eq!(Data::Bool(false), is_null(Expr::Null, true));
+        assert_eq!(Data::Bool(false), is_null(Expr::Number(Number::Int(0)), false));
+        assert_eq!(Data::Bool(true), is_null(Expr::Column(Column::new(String::from("missing"))), false));
+    }
+
+    #[test]
+    fn like_patterns() {
+        let cases = vec![
+            ("abc", "abc", true),
+            ("abc", "ab", false),
+            ("abc", "a%", true),
+            ("abc", "%c", true),
+            ("abc", "%b%", true),
+            ("abc", "a_c", true),
+            ("abc", "a_", false),
+            ("", "%", true),
+            ("", "_", false),
+            ("aXbXc", "%X%c", true),
+            ("abab", "%ab", true),
+            ("a%c", "a\\%c", true),
+            ("abc", "a\\%c", false),
+            ("a_c", "a\\_%", true),
+        ];
+
+        for (value, pattern, expected) in cases {
+            assert_eq!(expected, like(value, pattern), "{} like {}", value, pattern);
+        }
+    }
+
     #[test]
     fn eval_binary_op() {
         let cases = vec![
             (BinaryOp::Eq, Data::Bool(false), Data::Bool(false), Data::Bool(true)),
             (BinaryOp::Eq, Data::Bool(false), Data::Bool(true), Data::Bool(false)),
-            (BinaryOp::Eq, Data::Null, Data::Null, Data::Bool(true)),
-            (BinaryOp::Eq, Data::String(String::from("foo")), Data::Null, Data::Bool(false)),
+            (BinaryOp::Eq, Data::Null, Data::Null, Data::Null),
+            (BinaryOp::Eq, Data::String(String::from("foo")), Data::Null, Data::Null),
+            (BinaryOp::Neq, Data::Null, Data::from(1), Data::Null),
+            (BinaryOp::Lt, Data::from(1), Data::Null, Data::Null),
+            (BinaryOp::IsDistinctFrom, Data::Null, Data::Null, Data::Bool(false)),
+            (BinaryOp::IsDistinctFrom, Data::Null, Data::from(1), Data::Bool(true)),
+            (BinaryOp::IsNotDistinctFrom, Data::Null, Data::Null, Data::Bool(true)),
+            (BinaryOp::IsNotDistinctFrom, Data::from(1), Data::from(1.0), Data::Bool(true)),
+            (BinaryOp::And, Data::Null, Data::Bool(false), Data::Bool(false)),
+            (BinaryOp::And, Data::Bool(true), Data::Null, Data::Null),
+            (BinaryOp::Or, Data::Null, Data::Bool(true), Data::Bool(true)),
+            (BinaryOp::Or, Data::Bool(false), Data::Null, Data::Null),
+            (BinaryOp::Neq, Data::from(1), Data::from(2), Data::Bool(true)),
+            (BinaryOp::Neq, Data::from(1), Data::from(1.0), Data::Bool(false)),
+            (BinaryOp::Lt, Data::from(1), Data::from(2), Data::Bool(true)),
+            (BinaryOp::Lt, Data::from(2), Data::from(2), Data::Bool(false)),
+            (BinaryOp::Lte, Data::from(2), Data::from(2.0), Data::Bool(true)),
+            (BinaryOp::Gt, Data::from(2.5), Data::from(2), Data::Bool(true)),
+            (BinaryOp::Gt, Data::from("a"), Data::from("b"), Data::Bool(false)),
+            (BinaryOp::Gte, Data::from("b"), Data::from("b"), Data::Bool(true)),
+            (BinaryOp::And, Data::Bool(true), Data::Bool(false), Data::Bool(false)),
+            (BinaryOp::And, Data::Bool(true), Data::Bool(true), Data::Bool(true)),
+            (BinaryOp::Or, Data::Bool(true), Data::Bool(false), Data::Bool(true)),
+            (BinaryOp::Or, Data::Bool(false), Data::Bool(false), Data::Bool(false)),
+            (BinaryOp::Add, Data::from(1), Data::from(2), Data::from(3)),
+            (BinaryOp::Add, Data::from(1), Data::from(0.5), Data::from(1.5)),
+            (BinaryOp::Add, Data::from(1), Data::from("a"), Data::Null),
+            (BinaryOp::Add, Data::Number(Number::Int(i64::MAX)), Data::from(1), Data::Null),
+            (BinaryOp::Sub, Data::from(1), Data::from(2), Data::from(-1)),
+            (BinaryOp::Sub, Data::Number(Number::Int(i64::MIN)), Data::from(1), Data::Null),
+            (BinaryOp::Mul, Data::from(3), Data::from(2.5), Data::from(7.5)),
+            (BinaryOp::Mul, Data::Number(Number::Int(i64::MAX)), Data::from(2), Data::Null),
+            (BinaryOp::Div, Data::from(7), Data::from(2), Data::from(3)),
+            (BinaryOp::Div, Data::from(7), Data::from(2.0), Data::from(3.5)),
+            (BinaryOp::Div, Data::from(7), Data::from(0), Data::Null),
+            (BinaryOp::Div, Data::from(7.0), Data::from(0.0), Data::Null),
+            (BinaryOp::Div, Data::Number(Number::Int(i64::MIN)), Data::from(-1), Data::Null),
+            (BinaryOp::Rem, Data::from(7), Data::from(3), Data::from(1)),
+            (BinaryOp::Rem, Data::from(7.5), Data::from(2), Data::from(1.5)),
+            (BinaryOp::Rem, Data::from(7), Data::from(0), Data::Null),
         ];
 
         for (op, left, right, expected) in cases {
             assert_eq!(expected, op.eval(left, right));
         }
     }
+
+    #[test]
+    fn eval_unary_op() {
+        let cases = vec![
+            (UnaryOp::Not, Data::Bool(false), Data::Bool(true)),
+            (UnaryOp::Not, Data::Bool(true), Data::Bool(false)),
+            (UnaryOp::Not, Data::from(1), Data::Null),
+            (UnaryOp::Not, Data::Null, Data::Null),
+            (UnaryOp::Neg, Data::from(1), Data::from(-1)),
+            (UnaryOp::Neg, Data::from(-1.5), Data::from(1.5)),
+            (UnaryOp::Neg, Data::Number(Number::Int(i64::MIN)), Data::Null),
+            (UnaryOp::Neg, Data::from("a"), Data::Null),
+        ];
+
+        for (op, value, expected) in cases {
+            assert_eq!(expected, op.eval(value));
+        }
+    }
 }
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use data::Data;
+use expr::{BinaryOp, Column, Expr};
+use query::JoinKind;
+use row::Row;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Joins two relations. Equalities between an expression over the left tables and one over the
+/// right tables are matched with a hash table built over the smaller side. The rest of the
+/// condition is evaluated on each pair of rows that passes those equalities.
+pub fn join(left: Vec<Row>,
+            kind: &JoinKind,
+            right: Vec<Row>,
+            condition: &Expr,
+            left_tables: &[&str],
+            right_tables: &[&str])
+            -> Vec<Row> {
+    let mut left_keys = Vec::new();
+    let mut right_keys = Vec::new();
+    let mut residual = Vec::new();
+
+    for conjunct in conjuncts(condition) {
+        if let &Expr::BinaryExpr{ref left, op: BinaryOp::Eq, ref right} = conjunct {
+            let sides = (side(left, left_tables, right_tables), side(right, left_tables, right_tables));
+            match sides {
+                (Some(Side::Left), Some(Side::Right)) => {
+                    left_keys.push(&**left);
+                    right_keys.push(&**right);
+                    continue;
+                },
+                (Some(Side::Right), Some(Side::Left)) => {
+                    left_keys.push(&**right);
+                    right_keys.push(&**left);
+                    continue;
+                },
+                _ => {},
+            }
+        }
+        residual.push(conjunct);
+    }
+
+    let mut left_matched = vec![false; left.len()];
+    let mut right_matched = vec![false; right.len()];
+    let mut rows = Vec::new();
+    {
+        let mut emit = |l: usize, r: usize| {
+            let row = combine(&left[l], &right[r]);
+            if residual.iter().all(|conjunct| conjunct.eval(&row) == Data::Bool(true)) {
+                left_matched[l] = true;
+                right_matched[r] = true;
+                rows.push(row);
+            }
+        };
+
+        if left_keys.is_empty() {
+            for l in 0..left.len() {
+                for r in 0..right.len() {
+                    emit(l, r);
+                }
+            }
+        } else if right.len() <= left.len() {
+            let table = build(&right, &right_keys);
+            for (l, row) in left.iter().enumerate() {
+                for &r in probe(&table, row, &left_keys) {
+                    emit(l, r);
+                }
+            }
+        } else {
+            let table = build(&left, &left_keys);
+            for (r, row) in right.iter().enumerate() {
+                for &l in probe(&table, row, &right_keys) {
+                    emit(l, r);
+                }
+            }
+        }
+    }
+
+    // Unmatched rows of an outer join are padded with nulls for the other side's columns.
+    if *kind == JoinKind::Left || *kind == JoinKind::Full {
+        let nulls = null_row(&right);
+        for (row, _) in left.iter().zip(left_matched).filter(|&(_, matched)| !matched) {
+            rows.push(combine(row, &nulls));
+        }
+    }
+    if *kind == JoinKind::Right || *kind == JoinKind::Full {
+        let nulls = null_row(&left);
+        for (row, _) in right.iter().zip(right_matched).filter(|&(_, matched)| !matched) {
+            rows.push(combine(&nulls, row));
+        }
+    }
+
+    rows
+}
+
+/// Returns a copy of `row` whose columns are qualified by `table`, so that they can't collide
+/// with columns of the same name from other tables.
+pub fn qualify(mut row: Row, table: &str) -> Row {
+    let mut qualified = Row::new();
+    for column in row.columns {
+        let value = row.fields.remove(&Expr::Column(column.clone())).unwrap_or(Data::Null);
+        qualified.insert(Column::qualified(table.to_owned(), column.name), value);
+    }
+    qualified
+}
+
+/// Splits a condition into the expressions that are and-ed together.
+fn conjuncts(condition: &Expr) -> Vec<&Expr> {
+    match condition {
+        &Expr::BinaryExpr{ref left, op: BinaryOp::And, ref right} => {
+            let mut exprs = conjuncts(left);
+            exprs.extend(conjuncts(right));
+            exprs
+        },
+        _ => vec![condition],
+    }
+}
+
+/// Returns the side whose tables every column of `expr` belongs to, if there is exactly one.
+fn side(expr: &Expr, left_tables: &[&str], right_tables: &[&str]) -> Option<Side> {
+    match expr {
+        &Expr::Column(Column{table: Some(ref table), ..}) => {
+            if left_tables.contains(&table.as_str()) {
+                Some(Side::Left)
+            } else if right_tables.contains(&table.as_str()) {
+                Some(Side::Right)
+            } else {
+                None
+            }
+        },
+        &Expr::Column(_) | &Expr::AggregateCall(_) => None,
+        _ => {
+            let mut sides = expr.children().into_iter().map(|child| side(child, left_tables, right_tables));
+            let first = match sides.next() {
+                Some(first) => first,
+                None => return None,
+            };
+            if sides.all(|side| side == first) { first } else { None }
+        },
+    }
+}
+
+/// Evaluates the join key of each row. Rows with a null in their key can't match anything, so
+/// they're left out.
+fn build(rows: &[Row], keys: &[&Expr]) -> HashMap<Vec<Data>, Vec<usize>> {
+    let mut table = HashMap::new();
+    for (index, row) in rows.iter().enumerate() {
+        let key: Vec<Data> = keys.iter().map(|key| key.eval(row)).collect();
+        if !key.contains(&Data::Null) {
+            table.entry(key).or_insert_with(Vec::new).push(index);
+        }
+    }
+    table
+}
+
+fn probe<'a>(table: &'a HashMap<Vec<Data>, Vec<usize>>, row: &Row, keys: &[&Expr]) -> &'a [usize] {
+    let key: Vec<Data> = keys.iter().map(|key| key.eval(row)).collect();
+    match table.get(&key) {
+        Some(indices) if !key.contains(&Data::Null) => indices,
+        _ => &[],
+    }
+}
+
+/// Returns a row with both sets of columns.
+fn combine(left: &Row, right: &Row) -> Row {
+    let mut row = left.clone();
+    row.fields.extend(right.fields.iter().map(|(expr, value)| (expr.clone(), value.clone())));
+    row.columns.extend(right.columns.iter().cloned());
+    row
+}
+
+/// Returns a row with a null for every column seen in `rows`.
+fn null_row(rows: &[Row]) -> Row {
+    let mut nulls = Row::new();
+    for row in rows {
+        for column in row.columns.iter() {
+            nulls.insert(column.clone(), Data::Null);
+        }
+    }
+    nulls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use row::make_rows;
+
+    fn qualified_rows(table: &str, columns: Vec<&'static str>, values: Vec<Vec<Data>>) -> Vec<Row> {
+        make_rows(columns, values).into_iter()
+            .map(|row| qualify(row.unwrap(), table))
+            .collect()
+    }
+
+    fn column(table: &str, name: &str) -> Box<Expr> {
+        Box::new(Expr::Column(Column::qualified(table.to_owned(), name.to_owned())))
+    }
+
+    fn select(rows: &[Row], columns: &[(&str, &str)]) -> Vec<Vec<Data>> {
+        rows.iter()
+            .map(|row| columns.iter().map(|&(table, name)| column(table, name).eval(row)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn join_kinds() {
+        let left = qualified_rows("a", vec!["id"], vec![data_vec![1], data_vec![2], data_vec![Data::Null]]);
+        let right = qualified_rows("b", vec!["id", "x"], vec![data_vec![2, "two"], data_vec![3, "three"]]);
+        let condition = Expr::BinaryExpr{left: column("b", "id"), op: BinaryOp::Eq, right: column("a", "id")};
+        let columns = [("a", "id"), ("b", "x")];
+
+        let cases = vec![
+            (JoinKind::Inner, vec![data_vec![2, "two"]]),
+            (JoinKind::Left, vec![data_vec![2, "two"], data_vec![1, Data::Null], data_vec![Data::Null, Data::Null]]),
+            (JoinKind::Right, vec![data_vec![2, "two"], data_vec![Data::Null, "three"]]),
+            (JoinKind::Full, vec![
+                data_vec![2, "two"],
+                data_vec![1, Data::Null],
+                data_vec![Data::Null, Data::Null],
+                data_vec![Data::Null, "three"],
+            ]),
+        ];
+
+        for (kind, expected) in cases {
+            let rows = join(left.clone(), &kind, right.clone(), &condition, &["a"], &["b"]);
+            assert_eq!(expected, select(&rows, &columns), "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn join_residual_condition() {
+        let left = qualified_rows("a", vec!["id", "n"], vec![data_vec![1, 1], data_vec![1, 5], data_vec![2, 1]]);
+        let right = qualified_rows("b", vec!["id", "n"], vec![data_vec![1, 3], data_vec![2, 3]]);
+        let columns = [("a", "n"), ("b", "id")];
+
+        // With a key, the hash table is built over the smaller side.
+        let condition = Expr::BinaryExpr {
+            left: Box::new(Expr::BinaryExpr{left: column("a", "id"), op: BinaryOp::Eq, right: column("b", "id")}),
+            op: BinaryOp::And,
+            right: Box::new(Expr::BinaryExpr{left: column("a", "n"), op: BinaryOp::Lt, right: column("b", "n")}),
+        };
+        let rows = join(left.clone(), &JoinKind::Inner, right.clone(), &condition, &["a"], &["b"]);
+        assert_eq!(vec![data_vec![1, 1], data_vec![1, 2]], select(&rows, &columns));
+
+        let rows = join(right.clone(), &JoinKind::Inner, left.clone(), &condition, &["b"], &["a"]);
+        assert_eq!(vec![data_vec![1, 1], data_vec![1, 2]], select(&rows, &columns));
+
+        // Without one, every pair of rows is compared.
+        let condition = Expr::BinaryExpr{left: column("a", "n"), op: BinaryOp::Gt, right: column("b", "n")};
+        let rows = join(left, &JoinKind::Inner, right, &condition, &["a"], &["b"]);
+        assert_eq!(vec![data_vec![5, 1], data_vec![5, 2]], select(&rows, &columns));
+    }
+}
@@ -6,6 +6,7 @@ pub mod answer;
 pub mod data;
 pub mod executor;
 pub mod expr;
+pub mod join;
 pub mod parser;
 pub mod query;
 pub mod scanner;
@@ -14,7 +15,7 @@ pub mod token;
 
 pub use answer::Answer;
 pub use data::{Data, Number};
-pub use executor::execute;
+pub use executor::{execute, execute_tables};
 pub use parser::parse;
 pub use query::Query;
 pub use source::open_file;
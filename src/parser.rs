@@ -1,7 +1,8 @@
 use std::iter::Peekable;
 use aggregate::{AggregateCall, AggregateFunction};
-use expr::{BinaryOp, Expr};
-use query::{OrderField, Query, SortDirection};
+use expr::{BinaryOp, Column, Expr, UnaryOp, IS_PRECEDENCE, PREDICATE_PRECEDENCE};
+use data::Number;
+use query::{CommonTableExpr, JoinKind, Limit, NullOrder, OrderField, Query, Relation, SelectItem, SortDirection};
 use scanner::Scanner;
 use token::Token;
 
@@ -30,16 +31,23 @@ impl<'a> Parser<'a> {
     }
 
     pub fn parse(&mut self) -> Result<Query> {
+        let with = match self.scanner.peek() {
+            Some(&Ok(Token::With)) => self.parse_with()?,
+            _ => vec![],
+        };
+
         self.expect(Token::Select)?;
+        let distinct = match self.scanner.peek() {
+            Some(&Ok(Token::Distinct)) => {
+                self.scanner.next();
+                true
+            },
+            _ => false,
+        };
         let select = self.parse_select()?;
 
         self.expect(Token::From)?;
-        let from = match self.scanner.next() {
-            Some(Ok(Token::Identifier(i))) => i,
-            Some(Ok(t)) => return Err(ParseError::UnexpectedToken(t)),
-            Some(Err(e)) => return Err(e.into()),
-            None => return Err(ParseError::UnexpectedEOF),
-        };
+        let from = self.parse_from()?;
 
         let condition = match self.scanner.peek().cloned() {
             Some(Ok(Token::Where)) => {
@@ -54,76 +62,463 @@ impl<'a> Parser<'a> {
             _ => vec![],
         };
 
+        let having = match self.scanner.peek().cloned() {
+            Some(Ok(Token::Having)) => {
+                self.scanner.next();
+                Some(self.parse_expr()?)
+            },
+            _ => None,
+        };
+
         let order = match self.scanner.peek().cloned() {
             Some(Ok(Token::Order)) => self.parse_order_by()?,
             _ => vec![],
         };
 
+        let limit = match self.scanner.peek().cloned() {
+            Some(Ok(Token::Limit)) => Some(self.parse_limit()?),
+            _ => None,
+        };
+
         Ok(Query {
+            with: with,
+            distinct: distinct,
             select: select,
             from: from,
             condition: condition,
             group: group,
+            having: having,
             order: order,
+            limit: limit,
         })
     }
 
     pub fn parse_expr(&mut self) -> Result<Expr> {
-        let left = match self.scanner.next() {
-            None => return Err(ParseError::UnexpectedEOF),
-            Some(Ok(Token::Identifier(i))) => self.parse_identifier(i)?,
-            Some(Ok(Token::Number(n))) => Expr::Number(n),
-            Some(Err(e)) => return Err(e.into()),
-            Some(Ok(t)) => return Err(ParseError::UnexpectedToken(t)),
+        self.parse_binary_expr(0)
+    }
+
+    /// Parses a chain of binary operators using precedence climbing. Only operators that bind at
+    /// least as tightly as `min_precedence` are consumed; the rest are left for the caller.
+    fn parse_binary_expr(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut left = self.parse_unary_expr()?;
+
+        loop {
+            let op = match self.scanner.peek().cloned() {
+                Some(Ok(Token::Is)) => {
+                    if IS_PRECEDENCE < min_precedence {
+                        return Ok(left);
+                    }
+                    self.scanner.next();
+                    left = self.parse_is(left)?;
+                    continue;
+                },
+                Some(Ok(Token::Not)) |
+                Some(Ok(Token::In)) |
+                Some(Ok(Token::Between)) |
+                Some(Ok(Token::Like)) |
+                Some(Ok(Token::ILike)) => {
+                    if PREDICATE_PRECEDENCE < min_precedence {
+                        return Ok(left);
+                    }
+                    left = self.parse_predicate(left)?;
+                    continue;
+                },
+                Some(Ok(t)) => match BinaryOp::maybe_from(&t) {
+                    Some(op) => op,
+                    None => return Ok(left),
+                },
+                Some(Err(e)) => return Err(e.into()),
+                None => return Ok(left),
+            };
+
+            if op.precedence() < min_precedence {
+                return Ok(left);
+            }
+            self.scanner.next();
+
+            let right = self.parse_binary_expr(op.precedence() + 1)?;
+            left = Expr::BinaryExpr{
+                left: Box::new(left),
+                op: op,
+                right: Box::new(right),
+            };
+        }
+    }
+
+    /// Parses the rest of `left is [not] null` or `left is [not] distinct from right`.
+    fn parse_is(&mut self, left: Expr) -> Result<Expr> {
+        let negated = match self.scanner.peek() {
+            Some(&Ok(Token::Not)) => {
+                self.scanner.next();
+                true
+            },
+            _ => false,
+        };
+
+        match self.scanner.next() {
+            Some(Ok(Token::Null)) => Ok(Expr::IsNull{
+                expr: Box::new(left),
+                negated: negated,
+            }),
+            Some(Ok(Token::Distinct)) => {
+                self.expect(Token::From)?;
+                let right = self.parse_binary_expr(IS_PRECEDENCE + 1)?;
+                let op = if negated {
+                    BinaryOp::IsNotDistinctFrom
+                } else {
+                    BinaryOp::IsDistinctFrom
+                };
+
+                Ok(Expr::BinaryExpr{
+                    left: Box::new(left),
+                    op: op,
+                    right: Box::new(right),
+                })
+            },
+            Some(Ok(t)) => Err(ParseError::UnexpectedToken(t)),
+            Some(Err(e)) => Err(e.into()),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    /// Parses the rest of `left [not] in (...)`, `left [not] between low and high` or
+    /// `left [not] like pattern`.
+    fn parse_predicate(&mut self, left: Expr) -> Result<Expr> {
+        let negated = match self.scanner.peek() {
+            Some(&Ok(Token::Not)) => {
+                self.scanner.next();
+                true
+            },
+            _ => false,
         };
 
-        let next = match self.scanner.peek().cloned() {
-            Some(Ok(t)) => t,
-            Some(Err(e)) => return Err(e.into()),
-            None => return Ok(left),
+        match self.scanner.next() {
+            Some(Ok(Token::In)) => {
+                self.expect(Token::OpenParen)?;
+                if let Some(&Ok(Token::Select)) | Some(&Ok(Token::With)) = self.scanner.peek() {
+                    let query = self.parse()?;
+                    self.expect(Token::CloseParen)?;
+                    return Ok(Expr::InSubquery{
+                        expr: Box::new(left),
+                        query: Box::new(query),
+                        negated: negated,
+                    });
+                }
+
+                let mut list = Vec::new();
+                loop {
+                    list.push(self.parse_expr()?);
+                    match self.scanner.next() {
+                        Some(Ok(Token::Comma)) => {},
+                        Some(Ok(Token::CloseParen)) => break,
+                        Some(Ok(t)) => return Err(ParseError::UnexpectedToken(t)),
+                        Some(Err(e)) => return Err(e.into()),
+                        None => return Err(ParseError::UnexpectedEOF),
+                    }
+                }
+
+                Ok(Expr::InList{
+                    expr: Box::new(left),
+                    list: list,
+                    negated: negated,
+                })
+            },
+            Some(Ok(Token::Between)) => {
+                // The bounds bind more tightly than `and` so that it separates them.
+                let low = self.parse_binary_expr(PREDICATE_PRECEDENCE + 1)?;
+                self.expect(Token::And)?;
+                let high = self.parse_binary_expr(PREDICATE_PRECEDENCE + 1)?;
+
+                Ok(Expr::Between{
+                    expr: Box::new(left),
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    negated: negated,
+                })
+            },
+            Some(Ok(t @ Token::Like)) | Some(Ok(t @ Token::ILike)) => {
+                let pattern = self.parse_binary_expr(PREDICATE_PRECEDENCE + 1)?;
+
+                Ok(Expr::Like{
+                    expr: Box::new(left),
+                    pattern: Box::new(pattern),
+                    negated: negated,
+                    case_insensitive: t == Token::ILike,
+                })
+            },
+            Some(Ok(t)) => Err(ParseError::UnexpectedToken(t)),
+            Some(Err(e)) => Err(e.into()),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    fn parse_unary_expr(&mut self) -> Result<Expr> {
+        let op = match self.scanner.peek().cloned() {
+            Some(Ok(t)) => UnaryOp::maybe_from(&t),
+            _ => None,
         };
 
-        let op = match BinaryOp::maybe_from(&next) {
-            Some(op) => op,
-            None => return Ok(left),
+        match op {
+            Some(op) => {
+                self.scanner.next();
+                let expr = self.parse_binary_expr(op.precedence())?;
+
+                // Fold negative literals so that they print and parse back as a single number.
+                if let (&UnaryOp::Neg, &Expr::Number(ref n)) = (&op, &expr) {
+                    if let Some(negated) = n.checked_neg() {
+                        return Ok(Expr::Number(negated));
+                    }
+                }
+
+                Ok(Expr::UnaryExpr{
+                    op: op,
+                    expr: Box::new(expr),
+                })
+            },
+            None => self.parse_primary_expr(),
+        }
+    }
+
+    fn parse_primary_expr(&mut self) -> Result<Expr> {
+        match self.scanner.next() {
+            None => Err(ParseError::UnexpectedEOF),
+            Some(Ok(Token::Identifier(i))) => self.parse_identifier(i),
+            Some(Ok(Token::Number(n))) => Ok(Expr::Number(n)),
+            Some(Ok(Token::String(s))) => Ok(Expr::String(s)),
+            Some(Ok(Token::True)) => Ok(Expr::Bool(true)),
+            Some(Ok(Token::False)) => Ok(Expr::Bool(false)),
+            Some(Ok(Token::Null)) => Ok(Expr::Null),
+            Some(Ok(Token::Case)) => self.parse_case(),
+            Some(Ok(Token::OpenParen)) => {
+                let expr = match self.scanner.peek() {
+                    Some(&Ok(Token::Select)) | Some(&Ok(Token::With)) => Expr::Subquery(Box::new(self.parse()?)),
+                    _ => self.parse_expr()?,
+                };
+                self.expect(Token::CloseParen)?;
+                Ok(expr)
+            },
+            Some(Ok(Token::Exists)) => {
+                self.expect(Token::OpenParen)?;
+                let query = self.parse()?;
+                self.expect(Token::CloseParen)?;
+                Ok(Expr::Exists(Box::new(query)))
+            },
+            Some(Err(e)) => Err(e.into()),
+            Some(Ok(t)) => Err(ParseError::UnexpectedToken(t)),
+        }
+    }
+
+    /// Parses the rest of a case expression after `case`.
+    fn parse_case(&mut self) -> Result<Expr> {
+        let operand = match self.scanner.peek() {
+            Some(&Ok(Token::When)) => None,
+            _ => Some(Box::new(self.parse_expr()?)),
         };
-        self.scanner.next();
 
-        Ok(Expr::BinaryExpr{
-            left: Box::new(left),
-            op: op,
-            right: Box::new(self.parse_expr()?),
+        let mut branches = Vec::new();
+        loop {
+            match self.scanner.peek().cloned() {
+                Some(Ok(Token::When)) => self.scanner.next(),
+                _ if !branches.is_empty() => break,
+                Some(Ok(t)) => return Err(ParseError::UnexpectedToken(t)),
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(ParseError::UnexpectedEOF),
+            };
+
+            let when = self.parse_expr()?;
+            self.expect(Token::Then)?;
+            let then = self.parse_expr()?;
+            branches.push((when, then));
+        }
+
+        let else_result = match self.scanner.peek() {
+            Some(&Ok(Token::Else)) => {
+                self.scanner.next();
+                Some(Box::new(self.parse_expr()?))
+            },
+            _ => None,
+        };
+        self.expect(Token::End)?;
+
+        Ok(Expr::Case{
+            operand: operand,
+            branches: branches,
+            else_result: else_result,
         })
     }
 
-    fn parse_select(&mut self) -> Result<Vec<Expr>> {
-        let mut exprs = Vec::new();
+    fn parse_select(&mut self) -> Result<Vec<SelectItem>> {
+        let mut items = Vec::new();
         loop {
-            exprs.push(self.parse_expr()?);
+            let item = match self.scanner.peek().cloned() {
+                Some(Ok(Token::Star)) => {
+                    self.scanner.next();
+                    SelectItem::Wildcard
+                },
+                _ => SelectItem::Expr {
+                    expr: self.parse_expr()?,
+                    alias: self.parse_alias()?,
+                },
+            };
+            items.push(item);
+
             match self.scanner.peek().cloned() {
                 Some(Ok(Token::Comma)) => self.scanner.next(),
-                _ => return Ok(exprs),
+                _ => return Ok(items),
             };
         }
     }
 
-    fn parse_identifier(&mut self, identifier: String) -> Result<Expr> {
+    /// Parses `with name as (query), ...`, the common table expressions before a select.
+    fn parse_with(&mut self) -> Result<Vec<CommonTableExpr>> {
+        self.expect(Token::With)?;
+
+        let mut ctes = Vec::new();
+        loop {
+            let name = self.parse_name()?;
+            self.expect(Token::As)?;
+            self.expect(Token::OpenParen)?;
+            let query = self.parse()?;
+            self.expect(Token::CloseParen)?;
+            ctes.push(CommonTableExpr {
+                name: name,
+                query: query,
+            });
+
+            match self.scanner.peek() {
+                Some(&Ok(Token::Comma)) => self.scanner.next(),
+                _ => return Ok(ctes),
+            };
+        }
+    }
+
+    /// Parses a table followed by any number of joins, which nest to the left.
+    fn parse_from(&mut self) -> Result<Relation> {
+        let mut relation = self.parse_table()?;
+
+        loop {
+            let kind = match self.scanner.peek().cloned() {
+                Some(Ok(Token::Join)) => JoinKind::Inner,
+                Some(Ok(Token::Inner)) => {
+                    self.scanner.next();
+                    JoinKind::Inner
+                },
+                Some(Ok(Token::Left)) => {
+                    self.scanner.next();
+                    JoinKind::Left
+                },
+                Some(Ok(Token::Right)) => {
+                    self.scanner.next();
+                    JoinKind::Right
+                },
+                Some(Ok(Token::Full)) => {
+                    self.scanner.next();
+                    JoinKind::Full
+                },
+                _ => return Ok(relation),
+            };
+            self.expect(Token::Join)?;
+
+            let right = self.parse_table()?;
+            self.expect(Token::On)?;
+            let condition = self.parse_expr()?;
+
+            relation = Relation::Join {
+                left: Box::new(relation),
+                kind: kind,
+                right: Box::new(right),
+                condition: condition,
+            };
+        }
+    }
+
+    /// Parses a file name or a parenthesized query, which must have an alias.
+    fn parse_table(&mut self) -> Result<Relation> {
         if let Some(&Ok(Token::OpenParen)) = self.scanner.peek() {
             self.scanner.next();
-            let argument = self.parse_expr()?;
+            let query = self.parse()?;
             self.expect(Token::CloseParen)?;
 
+            return match self.parse_alias()? {
+                Some(alias) => Ok(Relation::Subquery{query: Box::new(query), alias: alias}),
+                None => match self.scanner.next() {
+                    Some(Ok(t)) => Err(ParseError::UnexpectedToken(t)),
+                    Some(Err(e)) => Err(e.into()),
+                    None => Err(ParseError::UnexpectedEOF),
+                },
+            };
+        }
+
+        let name = self.parse_name()?;
+        let alias = self.parse_alias()?;
+
+        Ok(Relation::Table {
+            name: name,
+            alias: alias,
+        })
+    }
+
+    fn parse_name(&mut self) -> Result<String> {
+        match self.scanner.next() {
+            Some(Ok(Token::Identifier(i))) => Ok(i),
+            Some(Ok(t)) => Err(ParseError::UnexpectedToken(t)),
+            Some(Err(e)) => Err(e.into()),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    /// Parses an optional `as alias` or bare `alias`.
+    fn parse_alias(&mut self) -> Result<Option<String>> {
+        match self.scanner.peek().cloned() {
+            Some(Ok(Token::As)) => {
+                self.scanner.next();
+                self.parse_name().map(Some)
+            },
+            Some(Ok(Token::Identifier(i))) => {
+                self.scanner.next();
+                Ok(Some(i))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_identifier(&mut self, identifier: String) -> Result<Expr> {
+        if let Some(&Ok(Token::OpenParen)) = self.scanner.peek() {
+            self.scanner.next();
+
             let aggregate_function = match AggregateFunction::from_name(&identifier) {
                 Some(func) => func,
                 None => return Err(ParseError::UnknownFunction(identifier)),
             };
 
+            let distinct = match self.scanner.peek() {
+                Some(&Ok(Token::Distinct)) => {
+                    self.scanner.next();
+                    true
+                },
+                _ => false,
+            };
+
+            let argument = match self.scanner.peek().cloned() {
+                Some(Ok(Token::Star)) if aggregate_function == AggregateFunction::Count && !distinct => {
+                    self.scanner.next();
+                    None
+                },
+                _ => Some(Box::new(self.parse_expr()?)),
+            };
+            self.expect(Token::CloseParen)?;
+
             Ok(Expr::AggregateCall(AggregateCall{
                 function: aggregate_function,
-                argument: Box::new(argument),
+                argument: argument,
+                distinct: distinct,
             }))
+        } else if let Some(&Ok(Token::Dot)) = self.scanner.peek() {
+            self.scanner.next();
+            let name = self.parse_name()?;
+            Ok(Expr::Column(Column::qualified(identifier, name)))
         } else {
-            Ok(Expr::Column(identifier))
+            Ok(Expr::Column(Column::new(identifier)))
         }
     }
 
@@ -160,9 +555,29 @@ impl<'a> Parser<'a> {
                 _ => None,
             };
 
+            // `nulls`, `first` and `last` are only keywords here, so they can still name columns.
+            let nulls = match self.scanner.peek().cloned() {
+                Some(Ok(Token::Identifier(ref word))) if word.eq_ignore_ascii_case("nulls") => {
+                    self.scanner.next();
+                    match self.scanner.next() {
+                        Some(Ok(Token::Identifier(ref word))) if word.eq_ignore_ascii_case("first") => {
+                            Some(NullOrder::First)
+                        },
+                        Some(Ok(Token::Identifier(ref word))) if word.eq_ignore_ascii_case("last") => {
+                            Some(NullOrder::Last)
+                        },
+                        Some(Ok(t)) => return Err(ParseError::UnexpectedToken(t)),
+                        Some(Err(e)) => return Err(e.into()),
+                        None => return Err(ParseError::UnexpectedEOF),
+                    }
+                },
+                _ => None,
+            };
+
             fields.push(OrderField {
                 expr: expr,
                 direction: direction,
+                nulls: nulls,
             });
 
             match self.scanner.peek().cloned() {
@@ -172,6 +587,33 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn parse_limit(&mut self) -> Result<Limit> {
+        self.expect(Token::Limit)?;
+        let count = self.parse_count()?;
+
+        let offset = match self.scanner.peek().cloned() {
+            Some(Ok(Token::Offset)) => {
+                self.scanner.next();
+                self.parse_count()?
+            },
+            _ => 0,
+        };
+
+        Ok(Limit {
+            count: count,
+            offset: offset,
+        })
+    }
+
+    fn parse_count(&mut self) -> Result<usize> {
+        match self.scanner.next() {
+            Some(Ok(Token::Number(Number::Int(i)))) if i >= 0 => Ok(i as usize),
+            Some(Ok(t)) => Err(ParseError::UnexpectedToken(t)),
+            Some(Err(e)) => Err(e.into()),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
     fn expect(&mut self, t: Token) -> Result<()> {
         match self.scanner.next() {
             Some(Ok(ref token)) if *token == t => Ok(()),
@@ -192,11 +634,153 @@ mod tests {
         parse(input).unwrap();
     }
 
+    #[test]
+    fn parse_distinct() {
+        assert!(parse("select distinct a, b from foo").unwrap().distinct);
+        assert!(!parse("select a, b from foo").unwrap().distinct);
+    }
+
+    #[test]
+    fn parse_wildcard() {
+        let query = parse("select *, a from foo").unwrap();
+        let expected = vec![
+            SelectItem::Wildcard,
+            SelectItem::Expr{expr: Expr::Column(Column::new(String::from("a"))), alias: None},
+        ];
+        assert_eq!(expected, query.select);
+
+        let actual = parse("select * * from foo");
+        assert_eq!(Err(ParseError::UnexpectedToken(Token::Star)), actual);
+    }
+
+    #[test]
+    fn parse_aliases() {
+        let query = parse("select a as x, b y, c from foo").unwrap();
+        let expected = vec![
+            SelectItem::Expr{expr: Expr::Column(Column::new(String::from("a"))), alias: Some(String::from("x"))},
+            SelectItem::Expr{expr: Expr::Column(Column::new(String::from("b"))), alias: Some(String::from("y"))},
+            SelectItem::Expr{expr: Expr::Column(Column::new(String::from("c"))), alias: None},
+        ];
+        assert_eq!(expected, query.select);
+
+        let actual = parse("select a as from foo");
+        assert_eq!(Err(ParseError::UnexpectedToken(Token::From)), actual);
+    }
+
+    #[test]
+    fn parse_joins() {
+        let table = |name: &str, alias: Option<&str>| Relation::Table {
+            name: String::from(name),
+            alias: alias.map(String::from),
+        };
+        let column = |table: &str, name: &str| {
+            Box::new(Expr::Column(Column::qualified(String::from(table), String::from(name))))
+        };
+        let equals = |left, right| Expr::BinaryExpr{left: left, op: BinaryOp::Eq, right: right};
+
+        let query = parse("select x.id from a x join b as y on x.id = y.id left join c on c.id = y.id").unwrap();
+        let expected = Relation::Join {
+            left: Box::new(Relation::Join {
+                left: Box::new(table("a", Some("x"))),
+                kind: JoinKind::Inner,
+                right: Box::new(table("b", Some("y"))),
+                condition: equals(column("x", "id"), column("y", "id")),
+            }),
+            kind: JoinKind::Left,
+            right: Box::new(table("c", None)),
+            condition: equals(column("c", "id"), column("y", "id")),
+        };
+        assert_eq!(expected, query.from);
+        assert_eq!(vec!["x", "y", "c"], query.from.table_names());
+
+        let invalid = vec![
+            ("select a from b join c", ParseError::UnexpectedEOF),
+            ("select a from b left c on a", ParseError::UnexpectedToken(Token::Identifier(String::from("c")))),
+            ("select a from b inner join c where a", ParseError::UnexpectedToken(Token::Where)),
+            ("select x. from b", ParseError::UnexpectedToken(Token::From)),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), parse(input));
+        }
+    }
+
+    #[test]
+    fn parse_subquery() {
+        let query = parse("select avg(total) from (select sum(amount) as total from t group by c) as sub").unwrap();
+        let inner = parse("select sum(amount) as total from t group by c").unwrap();
+        assert_eq!(Relation::Subquery{query: Box::new(inner), alias: String::from("sub")}, query.from);
+
+        let invalid = vec![
+            ("select a from (select a from t)", ParseError::UnexpectedEOF),
+            ("select a from (select a from t) where a", ParseError::UnexpectedToken(Token::Where)),
+            ("select a from (select a from t as x", ParseError::UnexpectedEOF),
+            ("select a from (t) as x", ParseError::UnexpectedToken(Token::Identifier(String::from("t")))),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), parse(input));
+        }
+    }
+
+    #[test]
+    fn parse_expression_subqueries() {
+        let inner = || Box::new(parse("select b from t").unwrap());
+        let a = || Box::new(Expr::Column(Column::new(String::from("a"))));
+
+        let expected = Expr::BinaryExpr{left: a(), op: BinaryOp::Gt, right: Box::new(Expr::Subquery(inner()))};
+        assert_eq!(Ok(expected), Parser::new("a > (select b from t)").parse_expr());
+
+        let expected = Expr::InSubquery{expr: a(), query: inner(), negated: true};
+        assert_eq!(Ok(expected), Parser::new("a not in (select b from t)").parse_expr());
+
+        let expected = Expr::UnaryExpr{op: UnaryOp::Not, expr: Box::new(Expr::Exists(inner()))};
+        assert_eq!(Ok(expected), Parser::new("not exists (select b from t)").parse_expr());
+
+        let invalid = vec![
+            ("exists select b from t", ParseError::UnexpectedToken(Token::Select)),
+            ("a in (select b from t", ParseError::UnexpectedEOF),
+            ("(select b from t) + (select)", ParseError::UnexpectedToken(Token::CloseParen)),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), Parser::new(input).parse_expr());
+        }
+    }
+
+    #[test]
+    fn parse_with() {
+        let query = parse("with a as (select x from t), b as (select y from a) select * from b").unwrap();
+        let expected = vec![
+            CommonTableExpr{name: String::from("a"), query: parse("select x from t").unwrap()},
+            CommonTableExpr{name: String::from("b"), query: parse("select y from a").unwrap()},
+        ];
+        assert_eq!(expected, query.with);
+        assert!(parse("select * from b").unwrap().with.is_empty());
+
+        let invalid = vec![
+            ("with a (select x from t) select * from a", ParseError::UnexpectedToken(Token::OpenParen)),
+            ("with a as (select x from t) as", ParseError::UnexpectedToken(Token::As)),
+            ("with a as select x from t select * from a", ParseError::UnexpectedToken(Token::Select)),
+            ("with select * from a", ParseError::UnexpectedToken(Token::Select)),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), parse(input));
+        }
+    }
+
     #[test]
     fn parse_condition() {
         let inputs = vec![
             "select a from foo where a",
             "select a from foo where a = b",
+            "select a from foo where a and b or not c",
+            "select a from foo where (a or b) and c",
+            "select a from foo where name = 'Alice'",
+            "select a from foo where frozen = true or balance = null",
+            "select a from foo where a is null and b is not null",
+            "select a from foo where a is distinct from b or a is not distinct from b + 1",
         ];
 
         for input in inputs {
@@ -217,6 +801,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_having_query() {
+        let inputs = vec![
+            "select a from foo group by a having count(b) > 1",
+            "select sum(b) from foo having sum(b) > 1 order by a",
+        ];
+
+        for input in inputs {
+            parse(input).unwrap();
+        }
+    }
+
     #[test]
     fn parse_order_query() {
         let inputs = vec![
@@ -227,11 +823,242 @@ mod tests {
             "select a, b from foo order by b asc, a",
             "select a, b from foo order by b, a desc",
             "select a, b from foo order by b asc, a desc",
+            "select a, b from foo order by b nulls first",
+            "select a, b from foo order by b desc nulls last, a asc nulls first",
+            "select a, b from foo order by b NULLS FIRST",
+            "select first, last from foo order by first nulls last, last",
+            "select nulls from foo where nulls > 1 order by nulls nulls first",
+        ];
+
+        for input in inputs {
+            parse(input).unwrap();
+        }
+
+        let query = parse("select first from foo order by last nulls first").unwrap();
+        let expected = OrderField {
+            expr: Expr::Column(Column::new(String::from("last"))),
+            direction: None,
+            nulls: Some(NullOrder::First),
+        };
+        assert_eq!(vec![expected], query.order);
+
+        let invalid = vec![
+            ("select a from foo order by a nulls", ParseError::UnexpectedEOF),
+            ("select a from foo order by a nulls b", ParseError::UnexpectedToken(Token::Identifier(String::from("b")))),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), parse(input));
+        }
+    }
+
+    #[test]
+    fn boolean_precedence() {
+        let a = || Box::new(Expr::Column(Column::new(String::from("a"))));
+        let b = || Box::new(Expr::Column(Column::new(String::from("b"))));
+        let c = || Box::new(Expr::Column(Column::new(String::from("c"))));
+
+        let actual = Parser::new("a or b and c").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: a(),
+            op: BinaryOp::Or,
+            right: Box::new(Expr::BinaryExpr{left: b(), op: BinaryOp::And, right: c()}),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("not a = b and c").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: Box::new(Expr::UnaryExpr{
+                op: UnaryOp::Not,
+                expr: Box::new(Expr::BinaryExpr{left: a(), op: BinaryOp::Eq, right: b()}),
+            }),
+            op: BinaryOp::And,
+            right: c(),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("a and b and c").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: Box::new(Expr::BinaryExpr{left: a(), op: BinaryOp::And, right: b()}),
+            op: BinaryOp::And,
+            right: c(),
+        };
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn is_precedence() {
+        let a = || Box::new(Expr::Column(Column::new(String::from("a"))));
+        let b = || Box::new(Expr::Column(Column::new(String::from("b"))));
+
+        let actual = Parser::new("not a = b is null").parse_expr().unwrap();
+        let expected = Expr::UnaryExpr{
+            op: UnaryOp::Not,
+            expr: Box::new(Expr::IsNull{
+                expr: Box::new(Expr::BinaryExpr{left: a(), op: BinaryOp::Eq, right: b()}),
+                negated: false,
+            }),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("a is not distinct from b and a").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: Box::new(Expr::BinaryExpr{left: a(), op: BinaryOp::IsNotDistinctFrom, right: b()}),
+            op: BinaryOp::And,
+            right: a(),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("a is b").parse_expr();
+        assert_eq!(Err(ParseError::UnexpectedToken(Token::Identifier(String::from("b")))), actual);
+    }
+
+    #[test]
+    fn predicate_precedence() {
+        let a = || Box::new(Expr::Column(Column::new(String::from("a"))));
+        let b = || Box::new(Expr::Column(Column::new(String::from("b"))));
+        let one = || Box::new(Expr::Number(Number::Int(1)));
+
+        let actual = Parser::new("a between b and 1 + 1 and a").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: Box::new(Expr::Between{
+                expr: a(),
+                low: b(),
+                high: Box::new(Expr::BinaryExpr{left: one(), op: BinaryOp::Add, right: one()}),
+                negated: false,
+            }),
+            op: BinaryOp::And,
+            right: a(),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("not a not in (1, b) = b").parse_expr().unwrap();
+        let expected = Expr::UnaryExpr{
+            op: UnaryOp::Not,
+            expr: Box::new(Expr::BinaryExpr{
+                left: Box::new(Expr::InList{expr: a(), list: vec![*one(), *b()], negated: true}),
+                op: BinaryOp::Eq,
+                right: b(),
+            }),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("a not ilike b").parse_expr().unwrap();
+        let expected = Expr::Like{expr: a(), pattern: b(), negated: true, case_insensitive: true};
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("a not b").parse_expr();
+        assert_eq!(Err(ParseError::UnexpectedToken(Token::Identifier(String::from("b")))), actual);
+    }
+
+    #[test]
+    fn arithmetic_precedence() {
+        let a = || Box::new(Expr::Column(Column::new(String::from("a"))));
+        let b = || Box::new(Expr::Column(Column::new(String::from("b"))));
+        let c = || Box::new(Expr::Column(Column::new(String::from("c"))));
+
+        let actual = Parser::new("a - b * c < -a").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: Box::new(Expr::BinaryExpr{
+                left: a(),
+                op: BinaryOp::Sub,
+                right: Box::new(Expr::BinaryExpr{left: b(), op: BinaryOp::Mul, right: c()}),
+            }),
+            op: BinaryOp::Lt,
+            right: Box::new(Expr::UnaryExpr{op: UnaryOp::Neg, expr: a()}),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("a - b - c").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: Box::new(Expr::BinaryExpr{left: a(), op: BinaryOp::Sub, right: b()}),
+            op: BinaryOp::Sub,
+            right: c(),
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("-2").parse_expr().unwrap();
+        assert_eq!(Expr::Number(Number::Int(-2)), actual);
+    }
+
+    #[test]
+    fn parse_aggregate_modifiers() {
+        let inputs = vec![
+            "select count(*) from foo",
+            "select count(distinct a), sum(distinct a + b) from foo",
         ];
 
         for input in inputs {
             parse(input).unwrap();
         }
+
+        let invalid = vec![
+            ("select sum(*) from foo", ParseError::UnexpectedToken(Token::Star)),
+            ("select count(distinct *) from foo", ParseError::UnexpectedToken(Token::Star)),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), parse(input));
+        }
+    }
+
+    #[test]
+    fn parse_limit_query() {
+        let query = parse("select a from foo order by a limit 10 offset 5").unwrap();
+        assert_eq!(Some(Limit{count: 10, offset: 5}), query.limit);
+
+        let query = parse("select a from foo limit 0").unwrap();
+        assert_eq!(Some(Limit{count: 0, offset: 0}), query.limit);
+
+        let invalid = vec![
+            ("select a from foo limit 1.5", ParseError::UnexpectedToken(Token::Number(Number::Float(1.5)))),
+            ("select a from foo limit -1", ParseError::UnexpectedToken(Token::Minus)),
+            ("select a from foo limit 1 offset", ParseError::UnexpectedEOF),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), parse(input));
+        }
+    }
+
+    #[test]
+    fn parse_case() {
+        let a = || Expr::Column(Column::new(String::from("a")));
+        let one = || Expr::Number(Number::Int(1));
+
+        let actual = Parser::new("case a when 1 then a end").parse_expr().unwrap();
+        let expected = Expr::Case{
+            operand: Some(Box::new(a())),
+            branches: vec![(one(), a())],
+            else_result: None,
+        };
+        assert_eq!(expected, actual);
+
+        let actual = Parser::new("case when a = 1 then 1 when a then a else null end + 1").parse_expr().unwrap();
+        let expected = Expr::BinaryExpr{
+            left: Box::new(Expr::Case{
+                operand: None,
+                branches: vec![
+                    (Expr::BinaryExpr{left: Box::new(a()), op: BinaryOp::Eq, right: Box::new(one())}, one()),
+                    (a(), a()),
+                ],
+                else_result: Some(Box::new(Expr::Null)),
+            }),
+            op: BinaryOp::Add,
+            right: Box::new(one()),
+        };
+        assert_eq!(expected, actual);
+
+        let invalid = vec![
+            ("case end", ParseError::UnexpectedToken(Token::End)),
+            ("case a else 1 end", ParseError::UnexpectedToken(Token::Else)),
+            ("case when a then 1", ParseError::UnexpectedEOF),
+            ("case when a 1 end", ParseError::UnexpectedToken(Token::Number(Number::Int(1)))),
+        ];
+
+        for (input, expected) in invalid {
+            assert_eq!(Err(expected), Parser::new(input).parse_expr());
+        }
     }
 
     #[test]
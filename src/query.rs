@@ -2,13 +2,17 @@ use std::fmt;
 use expr::Expr;
 use token::Token;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Query {
-    pub select: Vec<Expr>,
-    pub from: String,
+    pub with: Vec<CommonTableExpr>,
+    pub distinct: bool,
+    pub select: Vec<SelectItem>,
+    pub from: Relation,
     pub condition: Option<Expr>,
     pub group: Vec<Expr>,
+    pub having: Option<Expr>,
     pub order: Vec<OrderField>,
+    pub limit: Option<Limit>,
 }
 
 impl fmt::Display for Query {
@@ -16,9 +20,20 @@ impl fmt::Display for Query {
         let select: Vec<String> = self.select.iter()
             .map(|expr| format!("{}", expr))
             .collect();
-        write!(f, "{} {}", Token::Select, select.join(", "))?;
+        if !self.with.is_empty() {
+            let with: Vec<String> = self.with.iter()
+                .map(|cte| format!("{}", cte))
+                .collect();
+            write!(f, "{} {} ", Token::With, with.join(", "))?;
+        }
 
-        write!(f, " {} {}", Token::From, Token::Identifier(self.from.clone()))?;
+        write!(f, "{}", Token::Select)?;
+        if self.distinct {
+            write!(f, " {}", Token::Distinct)?;
+        }
+        write!(f, " {}", select.join(", "))?;
+
+        write!(f, " {} {}", Token::From, self.from)?;
 
         if let Some(ref condition) = self.condition {
             write!(f, " {} {}", Token::Where, condition)?;
@@ -31,6 +46,10 @@ impl fmt::Display for Query {
             write!(f, " {} {} {}", Token::Group, Token::By, group.join(", "))?;
         }
 
+        if let Some(ref having) = self.having {
+            write!(f, " {} {}", Token::Having, having)?;
+        }
+
         if !self.order.is_empty() {
             let order: Vec<String> = self.order.iter()
                 .map(|expr| format!("{}", expr))
@@ -38,14 +57,146 @@ impl fmt::Display for Query {
             write!(f, " {} {} {}", Token::Order, Token::By, order.join(", "))?;
         }
 
+        if let Some(ref limit) = self.limit {
+            write!(f, " {}", limit)?;
+        }
+
         Ok(())
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// `name as (query)` in a with clause. The name can be used in from clauses like a file name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CommonTableExpr {
+    pub name: String,
+    pub query: Query,
+}
+
+impl fmt::Display for CommonTableExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ({})", Token::Identifier(self.name.clone()), Token::As, self.query)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SelectItem {
+    /// `*`, which expands to every column of the source.
+    Wildcard,
+    Expr {
+        expr: Expr,
+        alias: Option<String>,
+    },
+}
+
+impl fmt::Display for SelectItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &SelectItem::Wildcard => write!(f, "{}", Token::Star),
+            &SelectItem::Expr{ref expr, alias: None} => write!(f, "{}", expr),
+            &SelectItem::Expr{ref expr, alias: Some(ref alias)} => {
+                write!(f, "{} {} {}", expr, Token::As, Token::Identifier(alias.clone()))
+            },
+        }
+    }
+}
+
+/// Where a query reads its rows from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Relation {
+    /// A file. Its columns are qualified by its alias if it has one, or else by its name.
+    Table {
+        name: String,
+        alias: Option<String>,
+    },
+    /// A nested query, whose answer is read like a table.
+    Subquery {
+        query: Box<Query>,
+        alias: String,
+    },
+    Join {
+        left: Box<Relation>,
+        kind: JoinKind,
+        right: Box<Relation>,
+        condition: Expr,
+    },
+}
+
+impl Relation {
+    /// Returns the names that columns of this relation can be qualified with, from left to right.
+    pub fn table_names(&self) -> Vec<&str> {
+        match self {
+            &Relation::Table{ref name, alias: None} => vec![name],
+            &Relation::Table{alias: Some(ref alias), ..} => vec![alias],
+            &Relation::Subquery{ref alias, ..} => vec![alias],
+            &Relation::Join{ref left, ref right, ..} => {
+                let mut names = left.table_names();
+                names.extend(right.table_names());
+                names
+            },
+        }
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Relation::Table{ref name, ref alias} => {
+                write!(f, "{}", Token::Identifier(name.clone()))?;
+                if let Some(ref alias) = *alias {
+                    write!(f, " {} {}", Token::As, Token::Identifier(alias.clone()))?;
+                }
+                Ok(())
+            },
+            &Relation::Subquery{ref query, ref alias} => {
+                write!(f, "({}) {} {}", query, Token::As, Token::Identifier(alias.clone()))
+            },
+            &Relation::Join{ref left, ref kind, ref right, ref condition} => {
+                write!(f, "{} {} {} {} {}", left, kind, right, Token::On, condition)
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+}
+
+impl fmt::Display for JoinKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &JoinKind::Inner => write!(f, "{}", Token::Join),
+            &JoinKind::Left => write!(f, "{} {}", Token::Left, Token::Join),
+            &JoinKind::Right => write!(f, "{} {}", Token::Right, Token::Join),
+            &JoinKind::Full => write!(f, "{} {}", Token::Full, Token::Join),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct OrderField {
     pub expr: Expr,
     pub direction: Option<SortDirection>,
+    pub nulls: Option<NullOrder>,
+}
+
+impl OrderField {
+    pub fn sort_direction(&self) -> SortDirection {
+        self.direction.clone().unwrap_or(SortDirection::Asc)
+    }
+
+    /// Nulls sort as if they were larger than any other value unless stated otherwise, so they
+    /// come last in ascending order and first in descending order.
+    pub fn null_order(&self) -> NullOrder {
+        match (&self.nulls, self.sort_direction()) {
+            (&Some(ref nulls), _) => nulls.clone(),
+            (&None, SortDirection::Asc) => NullOrder::Last,
+            (&None, SortDirection::Desc) => NullOrder::First,
+        }
+    }
 }
 
 impl fmt::Display for OrderField {
@@ -56,11 +207,15 @@ impl fmt::Display for OrderField {
             write!(f, " {}", direction)?;
         }
 
+        if let Some(ref nulls) = self.nulls {
+            write!(f, " nulls {}", nulls)?;
+        }
+
         Ok(())
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum SortDirection {
     Asc,
     Desc,
@@ -74,3 +229,36 @@ impl fmt::Display for SortDirection {
         }
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NullOrder {
+    First,
+    Last,
+}
+
+impl fmt::Display for NullOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &NullOrder::First => write!(f, "first"),
+            &NullOrder::Last => write!(f, "last"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Limit {
+    pub count: usize,
+    pub offset: usize,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", Token::Limit, self.count)?;
+
+        if self.offset != 0 {
+            write!(f, " {} {}", Token::Offset, self.offset)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,17 +1,38 @@
 use std::collections::HashMap;
 use data::Data;
-use expr::Expr;
+use expr::{Column, Expr};
 use source::SourceError;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Row {
+    /// The value of each column, keyed by `Expr::Column`. The columns of a joined row are
+    /// qualified by the table they came from. Grouped rows also hold the value of each aggregate
+    /// call.
     pub fields: HashMap<Expr, Data>,
+    /// The columns inserted with `insert` or `insert_column`, in insertion order.
+    pub columns: Vec<Column>,
+    /// In a grouped row, the value of each group by expression that isn't a column.
+    pub groups: HashMap<Expr, Data>,
 }
 
 impl Row {
     pub fn new() -> Self {
         Row {
             fields: HashMap::new(),
+            columns: Vec::new(),
+            groups: HashMap::new(),
+        }
+    }
+
+    /// Inserts a value for a source column, remembering its position so that `select *` can list
+    /// columns in the order the source provides them.
+    pub fn insert_column(&mut self, name: String, value: Data) {
+        self.insert(Column::new(name), value);
+    }
+
+    pub fn insert(&mut self, column: Column, value: Data) {
+        if self.fields.insert(Expr::Column(column.clone()), value).is_none() {
+            self.columns.push(column);
         }
     }
 }
@@ -22,8 +43,7 @@ pub fn make_rows(columns: Vec<&'static str>, values: Vec<Vec<Data>>) -> Vec<Resu
     for input_row in values {
         let mut row = Row::new();
         for (index, value) in input_row.into_iter().enumerate() {
-            let name = columns[index].to_owned();
-            row.fields.insert(Expr::Column(name), value);
+            row.insert_column(columns[index].to_owned(), value);
         }
         rows.push(Ok(row));
     }
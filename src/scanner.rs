@@ -50,10 +50,37 @@ impl<'a> Iterator for Scanner<'a> {
                 self.input.next();
                 Ok(Token::Comma)
             },
+            '.' => {
+                self.input.next();
+                Ok(Token::Dot)
+            },
             '=' => {
                 self.input.next();
                 Ok(Token::Eq)
             },
+            '+' => {
+                self.input.next();
+                Ok(Token::Plus)
+            },
+            '-' => {
+                self.input.next();
+                Ok(Token::Minus)
+            },
+            '*' => {
+                self.input.next();
+                Ok(Token::Star)
+            },
+            '/' => {
+                self.input.next();
+                Ok(Token::Slash)
+            },
+            '%' => {
+                self.input.next();
+                Ok(Token::Percent)
+            },
+            '!' => self.read_not_equal(),
+            '<' => Ok(self.read_less_than()),
+            '>' => Ok(self.read_greater_than()),
             '\'' => self.read_string(),
             '"' => self.read_quoted_identifier(),
             c if Self::is_letter(c) => Ok(self.read_identifier()),
@@ -79,6 +106,44 @@ impl<'a> Scanner<'a> {
         }
     }
 
+    fn read_not_equal(&mut self) -> Result<Token> {
+        assert_eq!(Some('!'), self.input.next());
+
+        match self.input.next() {
+            Some('=') => Ok(Token::Neq),
+            Some(_) => Err(ScanError::UnknownToken('!')),
+            None => Err(ScanError::UnexpectedEOF),
+        }
+    }
+
+    fn read_less_than(&mut self) -> Token {
+        assert_eq!(Some('<'), self.input.next());
+
+        match self.input.peek().cloned() {
+            Some('=') => {
+                self.input.next();
+                Token::Lte
+            },
+            Some('>') => {
+                self.input.next();
+                Token::Neq
+            },
+            _ => Token::Lt,
+        }
+    }
+
+    fn read_greater_than(&mut self) -> Token {
+        assert_eq!(Some('>'), self.input.next());
+
+        match self.input.peek().cloned() {
+            Some('=') => {
+                self.input.next();
+                Token::Gte
+            },
+            _ => Token::Gt,
+        }
+    }
+
     fn read_identifier(&mut self) -> Token {
         let mut buf = String::new();
         loop {
@@ -91,17 +156,7 @@ impl<'a> Scanner<'a> {
             };
         }
 
-        match buf.to_lowercase().as_ref() {
-            "select" => Token::Select,
-            "from" => Token::From,
-            "where" => Token::Where,
-            "group" => Token::Group,
-            "order" => Token::Order,
-            "by" => Token::By,
-            "asc" => Token::Asc,
-            "desc" => Token::Desc,
-            _ => Token::Identifier(buf),
-        }
+        Token::keyword(&buf).unwrap_or(Token::Identifier(buf))
     }
 
     fn read_string(&mut self) -> Result<Token> {
@@ -179,17 +234,47 @@ mod tests {
 
     #[test]
     fn symbols() {
-        let mut scanner = Scanner::new("(,)=");
+        let mut scanner = Scanner::new("(,)=.");
         assert_eq!(scanner.next(), Some(Ok(Token::OpenParen)));
         assert_eq!(scanner.next(), Some(Ok(Token::Comma)));
         assert_eq!(scanner.next(), Some(Ok(Token::CloseParen)));
         assert_eq!(scanner.next(), Some(Ok(Token::Eq)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Dot)));
+        assert_eq!(scanner.next(), None);
+    }
+
+    #[test]
+    fn comparison_operators() {
+        let mut scanner = Scanner::new("= != <> < <= > >= a<=b");
+        assert_eq!(scanner.next(), Some(Ok(Token::Eq)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Neq)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Neq)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Lt)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Lte)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Gt)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Gte)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Identifier("a".to_string()))));
+        assert_eq!(scanner.next(), Some(Ok(Token::Lte)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Identifier("b".to_string()))));
+        assert_eq!(scanner.next(), None);
+    }
+
+    #[test]
+    fn arithmetic_operators() {
+        let mut scanner = Scanner::new("+ - * / % -1");
+        assert_eq!(scanner.next(), Some(Ok(Token::Plus)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Minus)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Star)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Slash)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Percent)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Minus)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Number(Number::Int(1)))));
         assert_eq!(scanner.next(), None);
     }
 
     #[test]
     fn identifiers() {
-        let mut scanner = Scanner::new(r#"select FrOm foo where group order by asc desc "a field""#);
+        let mut scanner = Scanner::new(r#"select FrOm foo where group order by asc desc and OR not true False NULL distinct limit offset as having is in between like ILIKE case when then else end join inner left right full on exists with "a field""#);
         assert_eq!(scanner.next(), Some(Ok(Token::Select)));
         assert_eq!(scanner.next(), Some(Ok(Token::From)));
         assert_eq!(scanner.next(), Some(Ok(Token::Identifier(("foo".to_string())))));
@@ -199,6 +284,35 @@ mod tests {
         assert_eq!(scanner.next(), Some(Ok(Token::By)));
         assert_eq!(scanner.next(), Some(Ok(Token::Asc)));
         assert_eq!(scanner.next(), Some(Ok(Token::Desc)));
+        assert_eq!(scanner.next(), Some(Ok(Token::And)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Or)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Not)));
+        assert_eq!(scanner.next(), Some(Ok(Token::True)));
+        assert_eq!(scanner.next(), Some(Ok(Token::False)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Null)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Distinct)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Limit)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Offset)));
+        assert_eq!(scanner.next(), Some(Ok(Token::As)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Having)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Is)));
+        assert_eq!(scanner.next(), Some(Ok(Token::In)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Between)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Like)));
+        assert_eq!(scanner.next(), Some(Ok(Token::ILike)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Case)));
+        assert_eq!(scanner.next(), Some(Ok(Token::When)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Then)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Else)));
+        assert_eq!(scanner.next(), Some(Ok(Token::End)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Join)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Inner)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Left)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Right)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Full)));
+        assert_eq!(scanner.next(), Some(Ok(Token::On)));
+        assert_eq!(scanner.next(), Some(Ok(Token::Exists)));
+        assert_eq!(scanner.next(), Some(Ok(Token::With)));
         assert_eq!(scanner.next(), Some(Ok(Token::Identifier("a field".to_string()))));
         assert_eq!(scanner.next(), None);
     }
@@ -227,5 +341,8 @@ mod tests {
     fn unknown_token() {
         let mut scanner = Scanner::new("^");
         assert_eq!(scanner.next(), Some(Err(ScanError::UnknownToken('^'))));
+
+        let mut scanner = Scanner::new("!a");
+        assert_eq!(scanner.next(), Some(Err(ScanError::UnknownToken('!'))));
     }
 }
@@ -1,39 +1,156 @@
 extern crate csv;
 
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
-use data::Data;
-use expr::Expr;
+use std::str::FromStr;
+use data::{Data, Number};
 use row::Row;
 use source::{Source, SourceError};
 
+/// The type that every non-empty cell of a CSV column is converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    Int,
+    Float,
+    Bool,
+    String,
+}
+
+impl ColumnType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "int" | "integer" => Some(ColumnType::Int),
+            "float" | "double" => Some(ColumnType::Float),
+            "bool" | "boolean" => Some(ColumnType::Bool),
+            "string" | "text" => Some(ColumnType::String),
+            _ => None,
+        }
+    }
+
+    /// Picks the narrowest type that every non-empty value can be parsed as. Columns with no
+    /// non-empty values are strings.
+    pub fn infer<'a, I: IntoIterator<Item = &'a str>>(values: I) -> Self {
+        let mut is_int = true;
+        let mut is_float = true;
+        let mut is_bool = true;
+        let mut is_empty = true;
+
+        for value in values.into_iter().filter(|value| !value.is_empty()) {
+            is_empty = false;
+            is_int = is_int && parse_int(value).is_some();
+            is_float = is_float && parse_float(value).is_some();
+            is_bool = is_bool && parse_bool(value).is_some();
+        }
+
+        if is_empty {
+            ColumnType::String
+        } else if is_int {
+            ColumnType::Int
+        } else if is_float {
+            ColumnType::Float
+        } else if is_bool {
+            ColumnType::Bool
+        } else {
+            ColumnType::String
+        }
+    }
+
+    /// Converts a cell to this type. Empty cells are null. Cells that don't parse as this type,
+    /// which can happen past the sampled rows, fall back to a float for integer columns and to a
+    /// string otherwise.
+    pub fn convert(&self, value: &str) -> Data {
+        if value.is_empty() {
+            return Data::Null;
+        }
+
+        let converted = match self {
+            &ColumnType::Int => parse_int(value).or_else(|| parse_float(value)),
+            &ColumnType::Float => parse_float(value),
+            &ColumnType::Bool => parse_bool(value),
+            &ColumnType::String => None,
+        };
+
+        converted.unwrap_or_else(|| Data::String(value.to_owned()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvOptions {
+    /// How many rows to read before deciding the type of each column.
+    pub sample_size: usize,
+    /// Column types that override inference, keyed by header.
+    pub column_types: HashMap<String, ColumnType>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            sample_size: 100,
+            column_types: HashMap::new(),
+        }
+    }
+}
+
 pub struct CsvSource {
     headers: Vec<String>,
+    types: Vec<ColumnType>,
+    sample: VecDeque<csv::Result<csv::StringRecord>>,
     iter: csv::StringRecordsIntoIter<File>,
 }
 
 impl CsvSource {
     pub fn new(filename: &str) -> Result<Source, SourceError> {
+        Self::with_options(filename, &CsvOptions::default())
+    }
+
+    pub fn with_options(filename: &str, options: &CsvOptions) -> Result<Source, SourceError> {
+        Ok(Box::new(Self::open(filename, options)?))
+    }
+
+    pub fn open(filename: &str, options: &CsvOptions) -> Result<Self, SourceError> {
         let file = File::open(filename)?;
         let mut reader = csv::Reader::from_reader(file);
 
-        let headers = reader.headers()?
+        let headers: Vec<String> = reader.headers()?
             .iter()
             .map(|header| header.to_owned())
             .collect();
 
-        let records = reader.into_records();
-        let source = CsvSource {
+        // Records that fail to read are kept in the sample, so that they fail when they're reached
+        // rather than when the file is opened.
+        let mut records = reader.into_records();
+        let sample: VecDeque<_> = records.by_ref().take(options.sample_size).collect();
+
+        let types = headers.iter()
+            .enumerate()
+            .map(|(index, header)| match options.column_types.get(header) {
+                Some(column_type) => *column_type,
+                None => {
+                    let values = sample.iter()
+                        .filter_map(|record| record.as_ref().ok())
+                        .map(|record| record.get(index).unwrap_or(""));
+                    ColumnType::infer(values)
+                },
+            })
+            .collect();
+
+        Ok(CsvSource {
             headers: headers,
+            types: types,
+            sample: sample,
             iter: records,
-        };
-        Ok(Box::new(source))
+        })
+    }
+
+    pub fn headers(&self) -> &[String] {
+        &self.headers
     }
 }
 
 impl Iterator for CsvSource {
     type Item = Result<Row, SourceError>;
     fn next(&mut self) -> Option<Self::Item> {
-        let record = match self.iter.next() {
+        let record = match self.sample.pop_front().or_else(|| self.iter.next()) {
             None => return None,
             Some(Err(e)) => return Some(Err(e.into())),
             Some(Ok(rec)) => rec,
@@ -41,14 +158,34 @@ impl Iterator for CsvSource {
 
         let mut row = Row::new();
         for (index, field) in record.into_iter().enumerate() {
-            let column = Expr::Column(self.headers[index].clone());
-            row.fields.insert(column, Data::String(field.to_owned()));
+            row.insert_column(self.headers[index].clone(), self.types[index].convert(field));
         }
 
         Some(Ok(row))
     }
 }
 
+fn parse_int(value: &str) -> Option<Data> {
+    i64::from_str(value).ok().map(|i| Data::Number(Number::Int(i)))
+}
+
+fn parse_float(value: &str) -> Option<Data> {
+    // Rust also accepts words like "inf" and "NaN", which are more likely to be names than numbers.
+    if !value.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c)) {
+        return None;
+    }
+
+    f64::from_str(value).ok().map(|f| Data::Number(Number::Float(f)))
+}
+
+fn parse_bool(value: &str) -> Option<Data> {
+    match value.to_lowercase().as_ref() {
+        "true" => Some(Data::Bool(true)),
+        "false" => Some(Data::Bool(false)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,13 +199,72 @@ mod tests {
             make_rows(
                 vec!["id", "name", "balance", "frozen", "last_transaction_amount"],
                 vec![
-                    data_vec!["1000", "Alice", "15.50", "false", "-4.50"],
-                    data_vec!["1001", "Bob", "-50.08", "true", "-100.99"],
-                    data_vec!["1002", "Charlie", "0.00", "false", ""],
-                    data_vec!["1003", "Denise", "-1024.64", "true", "-1024.64"],
+                    data_vec![1000, "Alice", 15.5, false, -4.5],
+                    data_vec![1001, "Bob", -50.08, true, -100.99],
+                    data_vec![1002, "Charlie", 0.0, false, Data::Null],
+                    data_vec![1003, "Denise", -1024.64, true, -1024.64],
                 ],
             );
         let actual: Vec<Result<Row, SourceError>> = source.collect();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn csv_source_with_column_types() {
+        let mut options = CsvOptions::default();
+        options.sample_size = 1;
+        options.column_types.insert(String::from("id"), ColumnType::String);
+        options.column_types.insert(String::from("frozen"), ColumnType::String);
+
+        let source = CsvSource::with_options("fixtures/accounts.csv", &options).unwrap();
+        let expected =
+            make_rows(
+                vec!["id", "name", "balance", "frozen", "last_transaction_amount"],
+                vec![
+                    data_vec!["1000", "Alice", 15.5, "false", -4.5],
+                    data_vec!["1001", "Bob", -50.08, "true", -100.99],
+                    data_vec!["1002", "Charlie", 0.0, "false", Data::Null],
+                    data_vec!["1003", "Denise", -1024.64, "true", -1024.64],
+                ],
+            );
+        let actual: Vec<Result<Row, SourceError>> = source.collect();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn csv_source_sample_errors() {
+        // The second record is missing a field.
+        let source = open_file("fixtures/uneven.csv").unwrap();
+        let expected = make_rows(vec!["id", "name"], vec![data_vec![1, "Alice"], data_vec![3, "Charlie"]]);
+        let mut actual: Vec<Result<Row, SourceError>> = source.collect();
+        assert_eq!(3, actual.len());
+        assert!(actual.remove(1).is_err());
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn infer_column_type() {
+        let cases = vec![
+            (vec!["1", "-2", ""], ColumnType::Int),
+            (vec!["1", "2.5"], ColumnType::Float),
+            (vec!["1e3", "-0.5"], ColumnType::Float),
+            (vec!["true", "FALSE", ""], ColumnType::Bool),
+            (vec!["1", "true"], ColumnType::String),
+            (vec!["NaN", "inf"], ColumnType::String),
+            (vec!["", ""], ColumnType::String),
+            (vec![], ColumnType::String),
+        ];
+
+        for (values, expected) in cases {
+            assert_eq!(expected, ColumnType::infer(values));
+        }
+    }
+
+    #[test]
+    fn convert_unexpected_values() {
+        assert_eq!(Data::from(1.5), ColumnType::Int.convert("1.5"));
+        assert_eq!(Data::from("n/a"), ColumnType::Float.convert("n/a"));
+        assert_eq!(Data::from("yes"), ColumnType::Bool.convert("yes"));
+        assert_eq!(Data::Null, ColumnType::String.convert(""));
+    }
 }
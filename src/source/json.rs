@@ -1,10 +1,8 @@
 extern crate serde_json;
 
-use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Lines};
 use data::{Data, Number};
-use expr::Expr;
 use row::Row;
 use source::{Source, SourceError};
 
@@ -29,7 +27,7 @@ impl Iterator for JsonSource {
             Some(Ok(l)) => l,
         };
 
-        let map: HashMap<String, serde_json::Value> = match serde_json::from_str(&line) {
+        let map: serde_json::Map<String, serde_json::Value> = match serde_json::from_str(&line) {
             Ok(map) => map,
             Err(e) => return Some(Err(e.into())),
         };
@@ -50,7 +48,7 @@ impl Iterator for JsonSource {
                 _ => continue,
             };
 
-            row.fields.insert(Expr::Column(key), val);
+            row.insert_column(key, val);
         }
         return Some(Ok(row));
     }
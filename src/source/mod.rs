@@ -3,7 +3,7 @@ mod json;
 
 use std::error::Error;
 use std::path::Path;
-pub use self::csv::CsvSource;
+pub use self::csv::{ColumnType, CsvOptions, CsvSource};
 pub use self::json::JsonSource;
 use row::Row;
 
@@ -20,11 +20,43 @@ impl<E: Error> From<E> for SourceError {
     }
 }
 
+/// The rows of a table, along with its columns if the source knows them before reading any rows,
+/// like the header of a CSV file.
+pub struct Table {
+    pub columns: Option<Vec<String>>,
+    pub rows: Source,
+}
+
+impl From<Source> for Table {
+    fn from(rows: Source) -> Self {
+        Table {
+            columns: None,
+            rows: rows,
+        }
+    }
+}
+
 pub fn open_file(filename: &str) -> Result<Source, SourceError> {
+    open_file_with_options(filename, &CsvOptions::default())
+}
+
+/// Opens a file like `open_file`, using `csv_options` if it turns out to be a CSV file.
+pub fn open_file_with_options(filename: &str, csv_options: &CsvOptions) -> Result<Source, SourceError> {
+    open_table(filename, csv_options).map(|table| table.rows)
+}
+
+/// Opens a file like `open_file_with_options`, along with the header of a CSV file.
+pub fn open_table(filename: &str, csv_options: &CsvOptions) -> Result<Table, SourceError> {
     let path = Path::new(filename);
     match path.extension().and_then(|s| s.to_str()) {
-        Some("csv") => CsvSource::new(&filename),
-        Some("json") => JsonSource::new(&filename),
+        Some("csv") => {
+            let source = CsvSource::open(&filename, csv_options)?;
+            Ok(Table {
+                columns: Some(source.headers().to_vec()),
+                rows: Box::new(source),
+            })
+        },
+        Some("json") => JsonSource::new(&filename).map(Table::from),
         Some(e) => Err(SourceError { description: format!("unknown file extension: .{}", e) }),
         None => Err(SourceError { description: "unknown file type".to_owned() }),
     }
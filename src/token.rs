@@ -11,22 +11,106 @@ pub enum Token {
     By,
     Asc,
     Desc,
+    And,
+    Or,
+    Not,
+    True,
+    False,
+    Null,
+    Distinct,
+    Limit,
+    Offset,
+    As,
+    Having,
+    Is,
+    In,
+    Between,
+    Like,
+    ILike,
+    Case,
+    When,
+    Then,
+    Else,
+    End,
+    Join,
+    Inner,
+    Left,
+    Right,
+    Full,
+    On,
+    Exists,
+    With,
     Identifier(String),
     String(String),
     Number(Number),
     OpenParen,
     CloseParen,
     Comma,
+    Dot,
     Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
 }
 
 impl Token {
+    /// Returns the keyword token that `word` spells, ignoring case.
+    pub fn keyword(word: &str) -> Option<Token> {
+        match word.to_lowercase().as_ref() {
+            "select" => Some(Token::Select),
+            "from" => Some(Token::From),
+            "where" => Some(Token::Where),
+            "group" => Some(Token::Group),
+            "order" => Some(Token::Order),
+            "by" => Some(Token::By),
+            "asc" => Some(Token::Asc),
+            "desc" => Some(Token::Desc),
+            "and" => Some(Token::And),
+            "or" => Some(Token::Or),
+            "not" => Some(Token::Not),
+            "true" => Some(Token::True),
+            "false" => Some(Token::False),
+            "null" => Some(Token::Null),
+            "distinct" => Some(Token::Distinct),
+            "limit" => Some(Token::Limit),
+            "offset" => Some(Token::Offset),
+            "as" => Some(Token::As),
+            "having" => Some(Token::Having),
+            "is" => Some(Token::Is),
+            "in" => Some(Token::In),
+            "between" => Some(Token::Between),
+            "like" => Some(Token::Like),
+            "ilike" => Some(Token::ILike),
+            "case" => Some(Token::Case),
+            "when" => Some(Token::When),
+            "then" => Some(Token::Then),
+            "else" => Some(Token::Else),
+            "end" => Some(Token::End),
+            "join" => Some(Token::Join),
+            "inner" => Some(Token::Inner),
+            "left" => Some(Token::Left),
+            "right" => Some(Token::Right),
+            "full" => Some(Token::Full),
+            "on" => Some(Token::On),
+            "exists" => Some(Token::Exists),
+            "with" => Some(Token::With),
+            _ => None,
+        }
+    }
+
     fn format_string(f: &mut fmt::Formatter, input: &str, delimiter: char) -> fmt::Result {
         write!(f, "{}", delimiter)?;
         for c in input.chars() {
             match c {
                 '\n' => write!(f, r#"\n"#)?,
-                '\\' => write!(f, r#"\"#)?,
+                '\\' => write!(f, r#"\\"#)?,
                 c if c == delimiter => write!(f, r#"\{}"#, delimiter)?,
                 c => write!(f, "{}", c)?,
             }
@@ -36,7 +120,7 @@ impl Token {
     }
 
     fn format_identifier(f: &mut fmt::Formatter, input: &str) -> fmt::Result {
-        if Self::is_alphanumeric(input) {
+        if Self::is_alphanumeric(input) && Self::keyword(input).is_none() {
             return write!(f, "{}", input);
         }
 
@@ -59,13 +143,55 @@ impl fmt::Display for Token {
             &Token::By => write!(f, "by"),
             &Token::Asc => write!(f, "asc"),
             &Token::Desc => write!(f, "desc"),
+            &Token::And => write!(f, "and"),
+            &Token::Or => write!(f, "or"),
+            &Token::Not => write!(f, "not"),
+            &Token::True => write!(f, "true"),
+            &Token::False => write!(f, "false"),
+            &Token::Null => write!(f, "null"),
+            &Token::Distinct => write!(f, "distinct"),
+            &Token::Limit => write!(f, "limit"),
+            &Token::Offset => write!(f, "offset"),
+            &Token::As => write!(f, "as"),
+            &Token::Having => write!(f, "having"),
+            &Token::Is => write!(f, "is"),
+            &Token::In => write!(f, "in"),
+            &Token::Between => write!(f, "between"),
+            &Token::Like => write!(f, "like"),
+            &Token::ILike => write!(f, "ilike"),
+            &Token::Case => write!(f, "case"),
+            &Token::When => write!(f, "when"),
+            &Token::Then => write!(f, "then"),
+            &Token::Else => write!(f, "else"),
+            &Token::End => write!(f, "end"),
+            &Token::Join => write!(f, "join"),
+            &Token::Inner => write!(f, "inner"),
+            &Token::Left => write!(f, "left"),
+            &Token::Right => write!(f, "right"),
+            &Token::Full => write!(f, "full"),
+            &Token::On => write!(f, "on"),
+            &Token::Exists => write!(f, "exists"),
+            &Token::With => write!(f, "with"),
             &Token::Identifier(ref i) => Self::format_identifier(f, i),
             &Token::String(ref s) => Self::format_string(f, s, '\''),
+            // Whole floats keep their decimal point so that they still scan as floats.
+            &Token::Number(Number::Float(n)) if n.is_finite() && n.fract() == 0.0 => write!(f, "{}.0", n),
             &Token::Number(ref n) => write!(f, "{}", n),
             &Token::OpenParen => write!(f, "("),
             &Token::CloseParen => write!(f, ")"),
             &Token::Comma => write!(f, ","),
+            &Token::Dot => write!(f, "."),
             &Token::Eq => write!(f, "="),
+            &Token::Neq => write!(f, "!="),
+            &Token::Lt => write!(f, "<"),
+            &Token::Lte => write!(f, "<="),
+            &Token::Gt => write!(f, ">"),
+            &Token::Gte => write!(f, ">="),
+            &Token::Plus => write!(f, "+"),
+            &Token::Minus => write!(f, "-"),
+            &Token::Star => write!(f, "*"),
+            &Token::Slash => write!(f, "/"),
+            &Token::Percent => write!(f, "%"),
         }
     }
 }
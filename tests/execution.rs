@@ -3,8 +3,11 @@ extern crate sql;
 
 use sql::Answer;
 use sql::Data;
-use sql::execute;
+use sql::{execute, execute_tables};
+use sql::executor::ExecuteError;
+use sql::expr::{Column, Expr};
 use sql::row::make_rows;
+use sql::source::{open_file, open_table, CsvOptions, Source};
 
 #[test]
 fn query_execution() {
@@ -27,6 +30,184 @@ fn query_execution() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn min_max_execution() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![3, "x"],
+            data_vec![Data::Null, "z"],
+            data_vec![1, "y"],
+        ],
+    );
+
+    let query = sql::parse("select min(a), max(a), min(b), max(b) from bar").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["min(a)".to_string(), "max(a)".to_string(), "min(b)".to_string(), "max(b)".to_string()],
+        rows: vec![data_vec![1, 3, "x", "z"]],
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn count_star_and_distinct_execution() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 0],
+            data_vec![Data::Null, 0],
+            data_vec![1, 1],
+            data_vec![2, 1],
+            data_vec![Data::Null, 1],
+        ],
+    );
+
+    let query = sql::parse("select b, count(*), count(a), count(distinct a), sum(distinct a) from bar group by b order by b").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec![
+            "b".to_string(),
+            "count(*)".to_string(),
+            "count(a)".to_string(),
+            "count(distinct a)".to_string(),
+            "sum(distinct a)".to_string(),
+        ],
+        rows: vec![
+            data_vec![0, 2, 1, 1, 1],
+            data_vec![1, 3, 2, 2, 3],
+        ],
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn select_wildcard() {
+    let input = make_rows(
+        vec!["b", "a"],
+        vec![
+            data_vec![1, 2],
+            data_vec![3, 4],
+        ],
+    );
+
+    let query = sql::parse("select *, a + b from bar order by a desc").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["b".to_string(), "a".to_string(), "a + b".to_string()],
+        rows: vec![
+            data_vec![3, 4, 7],
+            data_vec![1, 2, 3],
+        ],
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn select_wildcard_from_files() {
+    for filename in vec!["fixtures/accounts.csv", "fixtures/accounts.json"] {
+        let query = sql::parse("select * from bar where id = 1002").unwrap();
+        let source = sql::open_file(filename).unwrap();
+        let actual = execute(query, source).unwrap();
+        let expected = Answer {
+            columns: vec![
+                "id".to_string(),
+                "name".to_string(),
+                "balance".to_string(),
+                "frozen".to_string(),
+                "last_transaction_amount".to_string(),
+            ],
+            rows: vec![data_vec![1002, "Charlie", 0.0, false, Data::Null]],
+        };
+
+        assert_eq!(expected, actual);
+
+        // The columns come from the source, even when no rows match.
+        let query = sql::parse(&format!(r#"select * from "{}" where id = 0"#, filename)).unwrap();
+        let actual = execute_tables(query, |name: &str| open_table(name, &CsvOptions::default())).unwrap();
+        assert_eq!(expected.columns, actual.columns);
+        assert!(actual.rows.is_empty());
+    }
+}
+
+#[test]
+fn select_aliases() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 0],
+            data_vec![3, 1],
+            data_vec![5, 1],
+        ],
+    );
+
+    let query = sql::parse("select b as a, sum(a) total from bar group by b order by total desc, a").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["a".to_string(), "total".to_string()],
+        rows: vec![
+            data_vec![1, 8],
+            data_vec![0, 1],
+        ],
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn select_distinct() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, true],
+            data_vec![2, false],
+            data_vec![1, true],
+            data_vec![1.0, true],
+            data_vec![Data::Null, false],
+            data_vec![Data::Null, false],
+            data_vec![3, false],
+        ],
+    );
+
+    let query = sql::parse("select distinct a, b from bar").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["a".to_string(), "b".to_string()],
+        rows: vec![
+            data_vec![1, true],
+            data_vec![2, false],
+            data_vec![Data::Null, false],
+            data_vec![3, false],
+        ],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse("select distinct b from bar order by b limit 1 offset 1").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    assert_eq!(vec![data_vec![true]], actual.rows);
+
+    let query = sql::parse("select distinct b from bar limit 2").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    assert_eq!(vec![data_vec![true], data_vec![false]], actual.rows);
+
+    // Numbers that compare equal are the same value, however they're stored.
+    let input = make_rows(
+        vec!["a"],
+        vec![
+            data_vec![9007199254740993],
+            data_vec![9007199254740992.0],
+            data_vec![0],
+            data_vec![-0.0],
+        ],
+    );
+    let query = sql::parse("select count(distinct a) from bar").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    assert_eq!(vec![data_vec![2]], actual.rows);
+}
+
 #[test]
 fn filter_where_clause() {
     let input = make_rows(
@@ -48,6 +229,413 @@ fn filter_where_clause() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn filter_string_literal() {
+    let input = make_rows(
+        vec!["name", "balance"],
+        vec![
+            data_vec!["Alice", 1],
+            data_vec!["Bob", 2],
+            data_vec!["Alice", 4],
+        ],
+    );
+
+    let query = sql::parse("select sum(balance) from bar where name = 'Alice'").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["sum(balance)".to_string()],
+        rows: vec![data_vec![5]],
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn filter_nulls() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 1],
+            data_vec![2, Data::Null],
+            data_vec![4, 2],
+        ],
+    );
+
+    let cases = vec![
+        ("select sum(a) from bar where b = 1", 1),
+        ("select sum(a) from bar where b != 1", 4),
+        ("select sum(a) from bar where not b = 1", 4),
+        ("select sum(a) from bar where b is null", 2),
+        ("select sum(a) from bar where b is not null", 5),
+        ("select sum(a) from bar where b is distinct from 1", 6),
+    ];
+
+    for (input_query, expected) in cases {
+        let query = sql::parse(input_query).unwrap();
+        let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+        assert_eq!(vec![data_vec![expected]], actual.rows);
+    }
+}
+
+#[test]
+fn filter_predicates() {
+    let input = make_rows(
+        vec!["name", "balance"],
+        vec![
+            data_vec!["Alice", 1],
+            data_vec!["Bob", 2],
+            data_vec!["alan", 4],
+            data_vec![Data::Null, 8],
+        ],
+    );
+
+    let cases = vec![
+        ("select sum(balance) from bar where name in ('Alice', 'Bob')", 3),
+        ("select sum(balance) from bar where name not in ('Alice', 'Bob')", 4),
+        ("select sum(balance) from bar where balance between 2 and 4", 6),
+        ("select sum(balance) from bar where balance not between 2 and 4", 9),
+        ("select sum(balance) from bar where name like 'A%'", 1),
+        ("select sum(balance) from bar where name ilike 'a%'", 5),
+        ("select sum(balance) from bar where name not like 'A%'", 6),
+    ];
+
+    for (input_query, expected) in cases {
+        let query = sql::parse(input_query).unwrap();
+        let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+        assert_eq!(vec![data_vec![expected]], actual.rows, "{}", input_query);
+    }
+}
+
+#[test]
+fn filter_boolean_condition() {
+    let input = make_rows(
+        vec!["a", "b", "c"],
+        vec![
+            data_vec![1, true, 0],
+            data_vec![2, false, 0],
+            data_vec![4, true, 1],
+            data_vec![8, false, 1],
+        ],
+    );
+
+    let query = sql::parse("select sum(a) from bar where not b and c = 1 or b and c = 0").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["sum(a)".to_string()],
+        rows: vec![data_vec![9]],
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn arithmetic_in_select_and_aggregate() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 2],
+            data_vec![3, 4],
+        ],
+    );
+
+    let query = sql::parse("select a - b, a * 1.5 from bar").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["a - b".to_string(), "a * 1.5".to_string()],
+        rows: vec![
+            data_vec![-1, 1.5],
+            data_vec![-1, 4.5],
+        ],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse("select sum(a * 100 + b) from bar").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["sum(a * 100 + b)".to_string()],
+        rows: vec![data_vec![406]],
+    };
+    assert_eq!(expected, actual);
+
+    // Integer sums that overflow are null, like the arithmetic operators.
+    let query = sql::parse("select sum(id * 4611686018427387), avg(id * 4611686018427387) from \"fixtures/accounts.json\"").unwrap();
+    let actual = execute(query, open_file("fixtures/accounts.json").unwrap()).unwrap();
+    assert_eq!(vec![data_vec![Data::Null, Data::Null]], actual.rows);
+}
+
+#[test]
+fn case_execution() {
+    let input = make_rows(
+        vec!["balance", "frozen"],
+        vec![
+            data_vec![5, true],
+            data_vec![0, false],
+            data_vec![-3, true],
+            data_vec![7, false],
+        ],
+    );
+
+    let query = sql::parse("select case when balance > 0 then 'positive' when balance = 0 then 'zero' else 'negative' end as sign, count(*) from bar group by case when balance > 0 then 'positive' when balance = 0 then 'zero' else 'negative' end order by sign").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["sign".to_string(), "count(*)".to_string()],
+        rows: vec![
+            data_vec!["negative", 1],
+            data_vec!["positive", 2],
+            data_vec!["zero", 1],
+        ],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse("select sum(case when frozen then balance else 0 end) from bar").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    assert_eq!(vec![data_vec![2]], actual.rows);
+}
+
+#[test]
+fn join_execution() {
+    let query = sql::parse(r#"select a.name, count(t.id), max(t.amount) from "fixtures/accounts.csv" as a join "fixtures/transactions.json" as t on a.id = t.account_id group by a.name order by a.name"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    let expected = Answer {
+        columns: vec!["a.name".to_string(), "count(t.id)".to_string(), "max(t.amount)".to_string()],
+        rows: vec![
+            data_vec!["Alice", 1, -4.5],
+            data_vec!["Bob", 2, 50.91],
+            data_vec!["Denise", 1, -1024.64],
+        ],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse(r#"select * from "fixtures/accounts.csv" as a join "fixtures/transactions.json" as t on a.id = t.id"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    let columns = vec!["a.id", "a.name", "a.balance", "a.frozen", "a.last_transaction_amount", "t.id", "t.account_id", "t.amount"];
+    assert_eq!(columns, actual.columns);
+    assert!(actual.rows.is_empty());
+
+    let query = sql::parse(r#"select a.name, t.id from "fixtures/accounts.csv" a left join "fixtures/transactions.json" t on a.id = t.account_id and t.amount < 0 order by a.name, t.id"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    let expected = vec![
+        data_vec!["Alice", 1],
+        data_vec!["Bob", 2],
+        data_vec!["Charlie", Data::Null],
+        data_vec!["Denise", 4],
+    ];
+    assert_eq!(expected, actual.rows);
+
+    let query = sql::parse(r#"select t.id, a.name from "fixtures/accounts.csv" a full join "fixtures/transactions.json" t on a.id = t.account_id where a.name is null or t.id is null"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    let expected = vec![
+        data_vec![Data::Null, "Charlie"],
+        data_vec![5, Data::Null],
+    ];
+    assert_eq!(expected, actual.rows);
+}
+
+#[test]
+fn qualified_column_execution() {
+    let query = sql::parse(r#"select a.name, id from "fixtures/accounts.csv" as a where a.id = 1000"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    let expected = Answer {
+        columns: vec!["a.name".to_string(), "id".to_string()],
+        rows: vec![data_vec!["Alice", 1000]],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse(r#"select b.name from "fixtures/accounts.csv" as a"#).unwrap();
+    let actual = execute_tables(query, open_file);
+    assert_eq!(Err(ExecuteError::UnknownTable("b".to_string())), actual);
+}
+
+#[test]
+fn unqualified_join_columns() {
+    // Columns that only one table has don't need to be qualified, and table names work as
+    // qualifiers when there's no alias.
+    let query = sql::parse(r#"select name, amount from "fixtures/accounts.csv" join "fixtures/transactions.json" on "fixtures/accounts.csv".id = account_id where amount > 0"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    let expected = Answer {
+        columns: vec!["name".to_string(), "amount".to_string()],
+        rows: vec![data_vec!["Bob", 50.91]],
+    };
+    assert_eq!(expected, actual);
+
+    // Select aliases in the order clause aren't ambiguous.
+    let query = sql::parse(r#"select t.id as id from "fixtures/accounts.csv" a join "fixtures/transactions.json" t on a.id = account_id order by id desc"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    assert_eq!(vec![data_vec![4], data_vec![3], data_vec![2], data_vec![1]], actual.rows);
+
+    let invalid = vec![
+        (r#"select id from "fixtures/accounts.csv" a join "fixtures/transactions.json" t on a.id = account_id"#, "id"),
+        (r#"select a.name from "fixtures/accounts.csv" a join "fixtures/transactions.json" t on id = account_id"#, "id"),
+        (r#"select a.name from "fixtures/accounts.csv" a join "fixtures/accounts.csv" a on a.id = a.id"#, "a.name"),
+    ];
+
+    for (input, column) in invalid {
+        let query = sql::parse(input).unwrap();
+        let actual = execute_tables(query, open_file);
+        assert_eq!(Err(ExecuteError::AmbiguousColumn(column.to_string())), actual);
+    }
+
+    // Empty tables still have the columns of their header or select list.
+    let open = |name: &str| open_table(name, &CsvOptions::default());
+    let query = sql::parse(r#"select name, amount from "fixtures/accounts.csv" a left join "fixtures/empty.csv" t on a.id = account_id order by name limit 1"#).unwrap();
+    let actual = execute_tables(query, open).unwrap();
+    let expected = Answer {
+        columns: vec!["name".to_string(), "amount".to_string()],
+        rows: vec![data_vec!["Alice", Data::Null]],
+    };
+    assert_eq!(expected, actual);
+
+    let invalid = vec![
+        r#"select id from "fixtures/accounts.csv" a join "fixtures/empty.csv" t on a.id = t.account_id"#,
+        r#"select id from "fixtures/accounts.csv" a join (select id from "fixtures/transactions.json" where amount > 10000) t on a.id = t.id"#,
+    ];
+
+    for input in invalid {
+        let query = sql::parse(input).unwrap();
+        let actual = execute_tables(query, open);
+        assert_eq!(Err(ExecuteError::AmbiguousColumn("id".to_string())), actual);
+    }
+}
+
+#[test]
+fn subquery_execution() {
+    let query = sql::parse(r#"select count(*), min(total) from (select account_id, sum(amount) as total from "fixtures/transactions.json" group by account_id) as sub where sub.total < 0"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    assert_eq!(vec![data_vec![3, -1024.64]], actual.rows);
+
+    let query = sql::parse(r#"select a.name, s.total from "fixtures/accounts.csv" a join (select account_id, count(*) as total from "fixtures/transactions.json" group by account_id) as s on a.id = s.account_id order by total desc, name"#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    let expected = Answer {
+        columns: vec!["a.name".to_string(), "s.total".to_string()],
+        rows: vec![
+            data_vec!["Bob", 2],
+            data_vec!["Alice", 1],
+            data_vec!["Denise", 1],
+        ],
+    };
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn expression_subquery_execution() {
+    let cases = vec![
+        (r#"select name from "fixtures/accounts.csv" where balance > (select avg(balance) from "fixtures/accounts.json") order by name"#,
+         vec![data_vec!["Alice"], data_vec!["Bob"], data_vec!["Charlie"]]),
+        (r#"select name from "fixtures/accounts.csv" where id in (select account_id from "fixtures/transactions.json" where amount < 0) order by name"#,
+         vec![data_vec!["Alice"], data_vec!["Bob"], data_vec!["Denise"]]),
+        (r#"select name from "fixtures/accounts.csv" where id not in (select account_id from "fixtures/transactions.json")"#,
+         vec![data_vec!["Charlie"]]),
+        (r#"select count(*) from "fixtures/accounts.csv" where exists (select id from "fixtures/transactions.json" where amount > 100)"#,
+         vec![data_vec![0]]),
+        (r#"select count(*) from "fixtures/accounts.csv" where not exists (select id from "fixtures/transactions.json" where amount > 100)"#,
+         vec![data_vec![4]]),
+        (r#"select count(*) from "fixtures/accounts.csv" where exists (select * from "fixtures/transactions.json")"#,
+         vec![data_vec![4]]),
+        (r#"select (select max(id) from "fixtures/transactions.json" where id > 10) is null, (select min(id) from "fixtures/transactions.json") from "fixtures/accounts.csv" limit 1"#,
+         vec![data_vec![true, 1]]),
+    ];
+
+    for (input, expected) in cases {
+        let query = sql::parse(input).unwrap();
+        let actual = execute_tables(query, open_file).unwrap();
+        assert_eq!(expected, actual.rows, "{}", input);
+    }
+}
+
+#[test]
+fn subqueries_run_once() {
+    let mut opened = Vec::new();
+    let actual = {
+        let open = |name: &str| -> Result<Source, _> {
+            opened.push(name.to_owned());
+            open_file(name)
+        };
+        let query = sql::parse(r#"select (select count(*) from "fixtures/transactions.json") as n, name from "fixtures/accounts.csv" where id in (select account_id from "fixtures/transactions.json") and (select count(*) from "fixtures/transactions.json") > 1 order by name limit 1"#).unwrap();
+        execute_tables(query, open).unwrap()
+    };
+
+    let expected = Answer {
+        columns: vec!["n".to_string(), "name".to_string()],
+        rows: vec![data_vec![5, "Alice"]],
+    };
+    assert_eq!(expected, actual);
+    // The outer table is opened first, to check that the subqueries don't refer to its columns.
+    assert_eq!(vec!["fixtures/accounts.csv", "fixtures/transactions.json", "fixtures/transactions.json"], opened);
+}
+
+#[test]
+fn subqueries_without_rows() {
+    // Names that a subquery's tables might have aren't taken to refer to the outer query, even
+    // when the subquery doesn't read any rows.
+    let inputs = vec![
+        r#"select name from "fixtures/accounts.csv" where id in (select id from "fixtures/empty.json")"#,
+        r#"select name from "fixtures/accounts.csv" where id in (select id from "fixtures/transactions.json" limit 0)"#,
+        r#"select name from "fixtures/accounts.json" where id in (select id from "fixtures/empty.csv")"#,
+    ];
+
+    for input in inputs {
+        let query = sql::parse(input).unwrap();
+        let actual = execute_tables(query, |name: &str| open_table(name, &CsvOptions::default())).unwrap();
+        assert_eq!(vec!["name".to_string()], actual.columns);
+        assert!(actual.rows.is_empty());
+    }
+}
+
+#[test]
+fn with_execution() {
+    let mut opened = Vec::new();
+    let actual = {
+        let open = |name: &str| -> Result<Source, _> {
+            opened.push(name.to_owned());
+            open_file(name)
+        };
+        let query = sql::parse(r#"with debits as (select account_id, amount from "fixtures/transactions.json" where amount < 0), totals as (select account_id, sum(amount) as total from debits group by account_id) select a.name, t.total, (select count(*) from debits) as n from "fixtures/accounts.csv" as a join totals as t on a.id = t.account_id order by a.name"#).unwrap();
+        execute_tables(query, open).unwrap()
+    };
+
+    let expected = Answer {
+        columns: vec!["a.name".to_string(), "t.total".to_string(), "n".to_string()],
+        rows: vec![
+            data_vec!["Alice", -4.5, 3],
+            data_vec!["Bob", -100.99, 3],
+            data_vec!["Denise", -1024.64, 3],
+        ],
+    };
+    assert_eq!(expected, actual);
+    assert_eq!(vec!["fixtures/transactions.json", "fixtures/accounts.csv"], opened);
+
+    // A name that isn't a common table expression is still opened as a file.
+    let query = sql::parse(r#"with accounts as (select id from "fixtures/accounts.csv" where frozen) select count(*) from "fixtures/accounts.csv""#).unwrap();
+    let actual = execute_tables(query, open_file).unwrap();
+    assert_eq!(vec![data_vec![4]], actual.rows);
+}
+
+#[test]
+fn invalid_subqueries() {
+    let query = sql::parse(r#"select name from "fixtures/accounts.csv" a where exists (select id from "fixtures/transactions.json" t where t.account_id = a.id)"#).unwrap();
+    let actual = execute_tables(query, open_file);
+    assert_eq!(Err(ExecuteError::CorrelatedSubquery("a.id".to_string())), actual);
+
+    let query = sql::parse(r#"select name, (select count(*) from "fixtures/transactions.json" where account_id = balance) from "fixtures/accounts.json""#).unwrap();
+    let actual = execute_tables(query, open_file);
+    assert_eq!(Err(ExecuteError::CorrelatedSubquery("balance".to_string())), actual);
+
+    // The empty table's header shows that the innermost query doesn't have a balance column.
+    let query = sql::parse(r#"select name from "fixtures/accounts.json" where exists (select id from "fixtures/transactions.json" where id > (select max(balance) from "fixtures/empty.csv"))"#).unwrap();
+    let actual = execute_tables(query, |name: &str| open_table(name, &CsvOptions::default()));
+    assert_eq!(Err(ExecuteError::CorrelatedSubquery("balance".to_string())), actual);
+
+    let subquery = sql::parse(r#"select account_id from "fixtures/transactions.json""#).unwrap();
+    let query = sql::parse(&format!(r#"select name from "fixtures/accounts.csv" where id = ({})"#, subquery)).unwrap();
+    let actual = execute_tables(query, open_file);
+    assert_eq!(Err(ExecuteError::SubqueryRows(subquery)), actual);
+
+    let subquery = sql::parse(r#"select id, amount from "fixtures/transactions.json""#).unwrap();
+    let query = sql::parse(&format!(r#"select name from "fixtures/accounts.csv" where id in ({})"#, subquery)).unwrap();
+    let actual = execute_tables(query, open_file);
+    assert_eq!(Err(ExecuteError::SubqueryColumns(subquery)), actual);
+}
+
 #[test]
 fn group_query_execution() {
     let input = make_rows(
@@ -72,6 +660,153 @@ fn group_query_execution() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn multiple_aggregates_per_expression() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 0],
+            data_vec![3, 1],
+            data_vec![5, 1],
+            data_vec![10, 1],
+        ],
+    );
+
+    let query = sql::parse("select b, sum(a) / count(a), max(a) - min(a) from bar group by b having max(a) - min(a) > 0 or sum(a) = 1 order by b").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["b".to_string(), "sum(a) / count(a)".to_string(), "max(a) - min(a)".to_string()],
+        rows: vec![
+            data_vec![0, 1, 0],
+            data_vec![1, 6, 7],
+        ],
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn group_without_aggregates() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 0],
+            data_vec![3, 1],
+            data_vec![5, 1],
+        ],
+    );
+
+    let query = sql::parse("select b * 10 from bar group by b order by b * 10").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["b * 10".to_string()],
+        rows: vec![
+            data_vec![0],
+            data_vec![10],
+        ],
+    };
+
+    assert_eq!(expected, actual);
+
+    let query = sql::parse("select b * 10, (b * 10) + 1, count(*) from bar group by b * 10 order by b * 10").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    assert_eq!(vec![data_vec![0, 1, 1], data_vec![10, 11, 2]], actual.rows);
+}
+
+#[test]
+fn aggregate_empty_input() {
+    let input = make_rows(vec!["a"], vec![]);
+
+    let query = sql::parse("select count(*), sum(a), max(a) from bar").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["count(*)".to_string(), "sum(a)".to_string(), "max(a)".to_string()],
+        rows: vec![data_vec![0, 0, Data::Null]],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse("select a, count(*) from bar group by a").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    assert!(actual.rows.is_empty());
+}
+
+#[test]
+fn reject_ungrouped_columns() {
+    let inputs = vec![
+        ("select a, sum(b) from bar", "a"),
+        ("select a + b from bar group by a", "b"),
+        ("select a from bar group by a order by b", "b"),
+        ("select a from bar group by a having b > 1", "b"),
+        ("select sum(b) as b from bar group by a order by a, c", "c"),
+    ];
+
+    for (input, column) in inputs {
+        let source = make_rows(vec!["a", "b"], vec![]);
+        let query = sql::parse(input).unwrap();
+        let actual = execute(query, Box::new(source.into_iter()));
+        assert_eq!(Err(ExecuteError::UngroupedColumn(column.to_string())), actual);
+    }
+
+    // A wildcard selects every column, so each one must be grouped.
+    let inputs = vec![
+        (r#"select * from "fixtures/accounts.csv" group by frozen"#, "id"),
+        (r#"select *, count(*) from "fixtures/accounts.csv""#, "id"),
+        (r#"select * from "fixtures/accounts.json" group by id"#, "name"),
+    ];
+
+    for (input, column) in inputs {
+        let query = sql::parse(input).unwrap();
+        let actual = execute_tables(query, open_file);
+        assert_eq!(Err(ExecuteError::UngroupedColumn(column.to_string())), actual);
+    }
+
+    let query = sql::parse(r#"select * from (select id, frozen from "fixtures/accounts.csv") as a group by frozen, id"#).unwrap();
+    assert_eq!(4, execute_tables(query, open_file).unwrap().rows.len());
+}
+
+#[test]
+fn reject_misplaced_aggregates() {
+    let inputs = vec![
+        (r#"select * from "fixtures/accounts.csv" where sum(balance) > 0"#, "sum(balance)"),
+        (r#"select * from "fixtures/accounts.csv" where count(*) > 0"#, "count(*)"),
+        (r#"select count(*) from "fixtures/accounts.csv" group by max(id)"#, "max(id)"),
+        (r#"select * from "fixtures/accounts.csv" a join "fixtures/transactions.json" t on t.account_id = min(a.id)"#, "min(a.id)"),
+    ];
+
+    for (input, call) in inputs {
+        let query = sql::parse(input).unwrap();
+        match execute_tables(query, open_file) {
+            Err(ExecuteError::MisplacedAggregate(actual)) => assert_eq!(call, actual.to_string()),
+            actual => panic!("expected {} to be rejected, got {:?}", call, actual),
+        }
+    }
+}
+
+#[test]
+fn having_clause() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 0],
+            data_vec![3, 1],
+            data_vec![5, 1],
+            data_vec![7, 2],
+        ],
+    );
+
+    let query = sql::parse("select sum(a), b from bar group by b having count(a) > 1 or b = 0 order by b").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["sum(a)".to_string(), "b".to_string()],
+        rows: vec![
+            data_vec![1, 0],
+            data_vec![8, 1],
+        ],
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn order_by_default_direction() {
     let input = make_rows(
@@ -171,3 +906,157 @@ fn order_by_multiple_fields() {
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn limit_after_order() {
+    let input = make_rows(
+        vec!["a"],
+        vec![
+            data_vec![3],
+            data_vec![1],
+            data_vec![4],
+            data_vec![2],
+        ],
+    );
+
+    let query = sql::parse("select a from bar order by a desc limit 2 offset 1").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["a".to_string()],
+        rows: vec![
+            data_vec![3],
+            data_vec![2],
+        ],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse("select a from bar order by a limit 2 offset 10").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    assert!(actual.rows.is_empty());
+}
+
+#[test]
+fn order_by_hidden_expression() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 3],
+            data_vec![2, 1],
+            data_vec![3, 2],
+        ],
+    );
+
+    let query = sql::parse("select a from bar order by b").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["a".to_string()],
+        rows: vec![
+            data_vec![2],
+            data_vec![3],
+            data_vec![1],
+        ],
+    };
+    assert_eq!(expected, actual);
+
+    let query = sql::parse("select a from bar order by a - b * 2 desc limit 2").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["a".to_string()],
+        rows: vec![
+            data_vec![2],
+            data_vec![3],
+        ],
+    };
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn order_by_hidden_aggregate() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 0],
+            data_vec![3, 1],
+            data_vec![5, 1],
+            data_vec![7, 2],
+        ],
+    );
+
+    let query = sql::parse("select b from bar group by b order by count(*) desc, b").unwrap();
+    let actual = execute(query, Box::new(input.into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["b".to_string()],
+        rows: vec![
+            data_vec![1],
+            data_vec![0],
+            data_vec![2],
+        ],
+    };
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn order_by_position() {
+    let input = make_rows(
+        vec!["a", "b"],
+        vec![
+            data_vec![1, 3],
+            data_vec![2, 1],
+            data_vec![3, 2],
+        ],
+    );
+
+    let query = sql::parse("select a, b as c from bar order by 2 desc").unwrap();
+    let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+    let expected = Answer {
+        columns: vec!["a".to_string(), "c".to_string()],
+        rows: vec![
+            data_vec![1, 3],
+            data_vec![3, 2],
+            data_vec![2, 1],
+        ],
+    };
+    assert_eq!(expected, actual);
+
+    for input_query in vec!["select a from bar order by 0", "select a from bar order by 2"] {
+        let query = sql::parse(input_query).unwrap();
+        let actual = execute(query, Box::new(input.clone().into_iter()));
+        assert!(actual.is_err());
+    }
+}
+
+#[test]
+fn distinct_rejects_hidden_order() {
+    let input = make_rows(vec!["a", "b"], vec![data_vec![1, 2]]);
+
+    let query = sql::parse("select distinct a from bar order by b").unwrap();
+    let actual = execute(query, Box::new(input.into_iter()));
+    let expected = Err(ExecuteError::InvalidOrderClause(Expr::Column(Column::new("b".to_string()))));
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn order_nulls() {
+    let input = make_rows(
+        vec!["a"],
+        vec![
+            data_vec![2],
+            data_vec![Data::Null],
+            data_vec![1],
+        ],
+    );
+
+    let cases = vec![
+        ("select a from bar order by a", data_vec![1, 2, Data::Null]),
+        ("select a from bar order by a desc", data_vec![Data::Null, 2, 1]),
+        ("select a from bar order by a nulls first", data_vec![Data::Null, 1, 2]),
+        ("select a from bar order by a desc nulls last", data_vec![2, 1, Data::Null]),
+    ];
+
+    for (input_query, expected) in cases {
+        let query = sql::parse(input_query).unwrap();
+        let actual = execute(query, Box::new(input.clone().into_iter())).unwrap();
+        let actual: Vec<Data> = actual.rows.into_iter().map(|mut row| row.remove(0)).collect();
+        assert_eq!(expected, actual);
+    }
+}
@@ -12,11 +12,104 @@ fn numbers() {
     run_expr(Data::Number(Number::Float(3.14)), "3.14", None);
 }
 
+#[test]
+fn literals() {
+    run_expr(Data::from("foo"), "'foo'", None);
+    run_expr(Data::Bool(true), "true", None);
+    run_expr(Data::Bool(false), "FALSE", None);
+    run_expr(Data::Null, "null", None);
+}
+
 #[test]
 fn binary_exprs() {
     run_expr(Data::Bool(true), "0 = 0", None);
     run_expr(Data::Bool(false), "1 = 0", None);
-    run_expr(Data::Bool(true), "a = a", None);
+    // Missing columns are null, and null is never equal to anything.
+    run_expr(Data::Null, "a = a", None);
+    run_expr(Data::Bool(true), "1 != 0", None);
+    run_expr(Data::Bool(false), "1 <> 1", None);
+    run_expr(Data::Bool(true), "0 < 1", None);
+    run_expr(Data::Bool(true), "1 <= 1", None);
+    run_expr(Data::Bool(false), "0 > 1", None);
+    run_expr(Data::Bool(true), "1.5 >= 1", None);
+    run_expr(Data::Bool(false), "2 < 1.5", None);
+    run_expr(Data::Bool(true), "'Alice' = 'Alice'", None);
+    run_expr(Data::Bool(true), "'Alice' < 'Bob'", None);
+}
+
+#[test]
+fn arithmetic_exprs() {
+    run_expr(Data::Number(Number::Int(7)), "1 + 2 * 3", None);
+    run_expr(Data::Number(Number::Int(9)), "(1 + 2) * 3", None);
+    run_expr(Data::Number(Number::Int(-4)), "1 - 2 - 3", None);
+    run_expr(Data::Number(Number::Float(2.5)), "5 / 2.0", None);
+    run_expr(Data::Number(Number::Int(2)), "5 / 2", None);
+    run_expr(Data::Number(Number::Int(1)), "5 % 2", None);
+    run_expr(Data::Number(Number::Int(-5)), "-(2 + 3)", None);
+    run_expr(Data::Null, "1 / 0", None);
+    run_expr(Data::Bool(true), "1 + 1 = 2", None);
+}
+
+#[test]
+fn boolean_exprs() {
+    run_expr(Data::Bool(true), "1 = 1 and 2 = 2", None);
+    run_expr(Data::Bool(false), "1 = 1 and 2 = 3", None);
+    run_expr(Data::Bool(true), "1 = 0 or 2 = 2", None);
+    run_expr(Data::Bool(true), "1 = 0 and 1 = 0 or 1 = 1", None);
+    run_expr(Data::Bool(false), "1 = 0 and (1 = 0 or 1 = 1)", None);
+    run_expr(Data::Bool(true), "not 1 = 0", None);
+    run_expr(Data::Bool(false), "not (1 = 1 or 1 = 0)", None);
+}
+
+#[test]
+fn predicates() {
+    run_expr(Data::Bool(true), "1 in (0, 1, 2)", None);
+    run_expr(Data::Bool(false), "3 in (0, 1, 2)", None);
+    run_expr(Data::Bool(true), "3 not in (0, 1, 2)", None);
+    run_expr(Data::Bool(true), "1 in (null, 1)", None);
+    run_expr(Data::Null, "3 in (null, 1)", None);
+    run_expr(Data::Null, "3 not in (null, 1)", None);
+    run_expr(Data::Null, "null in (1)", None);
+    run_expr(Data::Bool(true), "2 between 1 and 3", None);
+    run_expr(Data::Bool(true), "1 between 1 and 1", None);
+    run_expr(Data::Bool(false), "0 between 1 and 3", None);
+    run_expr(Data::Bool(true), "0 not between 1 and 3", None);
+    run_expr(Data::Null, "2 between null and 3", None);
+    run_expr(Data::Bool(false), "0 between null and -1", None);
+    run_expr(Data::Bool(true), "'Alice' like 'A%'", None);
+    run_expr(Data::Bool(false), "'Alice' like 'a%'", None);
+    run_expr(Data::Bool(true), "'Alice' ilike 'a%'", None);
+    run_expr(Data::Bool(true), "'Alice' not like '_b%'", None);
+    run_expr(Data::Null, "null like 'a%'", None);
+    run_expr(Data::Null, "'a' not like null", None);
+}
+
+#[test]
+fn case_exprs() {
+    run_expr(Data::from("one"), "case 1 when 0 then 'zero' when 1 then 'one' else 'many' end", None);
+    run_expr(Data::from("many"), "case 5 when 0 then 'zero' when 1 then 'one' else 'many' end", None);
+    run_expr(Data::Null, "case 5 when 0 then 'zero' end", None);
+    run_expr(Data::Null, "case null when null then 'null' end", None);
+    run_expr(Data::from("b"), "case when 1 > 2 then 'a' when 2 > 1 then 'b' when true then 'c' end", None);
+    run_expr(Data::from("else"), "case when null then 'null' else 'else' end", None);
+}
+
+#[test]
+fn null_logic() {
+    run_expr(Data::Null, "null = null", None);
+    run_expr(Data::Null, "1 != null", None);
+    run_expr(Data::Null, "not null", None);
+    run_expr(Data::Bool(false), "null and 1 = 0", None);
+    run_expr(Data::Null, "null and 1 = 1", None);
+    run_expr(Data::Bool(true), "null or 1 = 1", None);
+    run_expr(Data::Null, "null or 1 = 0", None);
+    run_expr(Data::Bool(true), "null is null", None);
+    run_expr(Data::Bool(false), "1 is null", None);
+    run_expr(Data::Bool(true), "1 + null is null", None);
+    run_expr(Data::Bool(true), "1 is not null", None);
+    run_expr(Data::Bool(false), "null is distinct from null", None);
+    run_expr(Data::Bool(true), "null is distinct from 1", None);
+    run_expr(Data::Bool(true), "null is not distinct from null", None);
 }
 
 fn run_expr(expected: Data, expr: &str, row: Option<Row>) {
@@ -8,7 +8,51 @@ fn query_format() {
         "select sum(a), b from c group by b",
         "select a, b from c order by b",
         "select a, b from c group by a order by b",
+        "select a, sum(b) from c where b > 0 group by a having count(b) > 1 order by a limit 5",
+        "select a, b from c limit 10",
+        "select * from c",
+        "select distinct a, b from c order by a limit 2",
+        "select *, a * 2 from c where a > 1",
+        r#"select sum(a) as total, b as "b value" from c group by b order by total"#,
+        "select a, b from c order by b limit 10 offset 20",
+        "select a, b from c order by b desc nulls last, a nulls first",
+        "select first, last, nulls from c order by last nulls first, nulls desc",
         "select a, b from c where a",
+        "select a, b from c where a != b",
+        "select a, b from c where a < 1",
+        "select a, b from c where a >= 1.5",
+        "select a, b from c where a and b or c",
+        "select a, b from c where a and (b or c)",
+        "select a, b from c where a and (b and c)",
+        "select a, b from c where not a and not (b or c)",
+        "select a, b from c where not a = b",
+        "select a, b from c where a = (b = c)",
+        "select a + b * c, (a + b) * c, a - (b - c), -a, -(a + b) from c",
+        "select sum(a * 100), sum(a) / 2 from c where a % 2 = 1",
+        "select a / 2.0, a * 100.0, 0.25 from c where a > -3.0",
+        "select count(*), count(distinct a), avg(distinct a * 2) from c",
+        "select a from c where a > -1.5 and b < a - -2",
+        "select a from c where name = 'Alice' and frozen = false",
+        "select a is null, a = b is not null, a + 1 is distinct from b from c where not a is not distinct from b",
+        "select a from c where (a is null) = b",
+        "select a from c where a in (1, 2, 'x') and b not in (a + 1)",
+        "select a from c where a between 1 and b + 2 and b not between (a and b) and c",
+        r"select a from c where a like 'x%' or b not ilike '%\\_y' or not a like b",
+        "select case when a > 0 then 'positive' when a = 0 then 'zero' else 'negative' end as sign from c",
+        "select sum(case when frozen then balance else 0 end), case a when 1 then 2 end * 3 from c",
+        "select x.a, y.b from c as x join \"d.json\" as y on x.a = y.a left join e on e.a = x.a and e.b > 1",
+        "select * from c as x right join d on x.a = d.a full join e on true",
+        "select \"c.csv\".a, b from \"c.csv\" join d on \"c.csv\".a = d.a",
+        "select avg(total) from (select c, sum(a) as total from \"t.json\" group by c) as sub where sub.total > 0",
+        "select x.a from (select a from c) as x join (select distinct a from d limit 1) as y on x.a = y.a",
+        "select (select max(a) from d) + 1 as m from c where a > (select avg(a) from d where d.b = 1)",
+        "select a from c where a not in (select b from d) and exists (select b from d where b in (select e from f))",
+        "select a from c where not exists (select b from d) order by (select 1 from d) desc",
+        "with x as (select a from \"c.csv\"), \"y z\" as (select a from x where a > 1) select * from x join \"y z\" on x.a = \"y z\".a",
+        "select a from c where a in (with x as (select b from d) select b from x)",
+        "select a from c where a in (1) in (true) and (a = b) like 'x' = b",
+        r"select 'it\'s', 'back\\slash', 'new\nline', true, null from c",
+        r#"select "null", "select", "a b" from c"#,
         r#"select a, b from "fixtures/accounts.json""#,
     ];
 
@@ -17,3 +61,20 @@ fn query_format() {
         assert_eq!(input, format!("{}", query));
     }
 }
+
+#[test]
+fn query_format_normalizes_parentheses() {
+    let inputs = vec![
+        ("select a from c where (a and b) or c", "select a from c where a and b or c"),
+        ("select a from c where ((a))", "select a from c where a"),
+        ("select a from c where not (a = b)", "select a from c where not a = b"),
+        ("select a x from c", "select a as x from c"),
+    ];
+
+    for (input, expected) in inputs {
+        let query = sql::parse(input).unwrap();
+        let formatted = format!("{}", query);
+        assert_eq!(expected, formatted);
+        assert_eq!(query, sql::parse(&formatted).unwrap());
+    }
+}